@@ -0,0 +1,630 @@
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use glfw::{Action, Context as _, Key};
+use crate::camera::CameraSettings;
+use crate::clock::{Clock, FixedClock, RealTimeClock};
+use crate::error::{Error, Result};
+use crate::headless::Headless;
+use crate::params::{ParamSpec, Params};
+use crate::screenshot::{self, ScheduledScreenshot};
+
+/// Default window width used by the exercises
+pub const SCR_WIDTH: u32 = 800;
+/// Default window height used by the exercises
+pub const SCR_HEIGHT: u32 = 600;
+
+/// Where the exercises render to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// A glfw window on the desktop
+    #[default]
+    Window,
+    /// An offscreen framebuffer with no window, for machines without a display
+    Headless,
+}
+
+/// How the window occupies the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayMode {
+    /// A regular decorated window
+    #[default]
+    Windowed,
+    /// Covers the whole monitor at its current video mode, so switching away is instant
+    Borderless,
+    /// Exclusive fullscreen at the video mode closest to the requested size
+    Fullscreen,
+}
+
+/// Which OpenGL profile the context is created with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GlProfile {
+    /// Only the modern API, as used throughout learnopengl.com
+    #[default]
+    Core,
+    /// Also keeps the deprecated fixed function API
+    Compatibility,
+}
+
+/// How the window (or offscreen framebuffer) and its GL context are created
+#[derive(Clone, Debug)]
+pub struct WindowSettings {
+    /// Width of the window in screen coordinates, or of the offscreen framebuffer in pixels
+    pub width: u32,
+    /// Height of the window in screen coordinates, or of the offscreen framebuffer in pixels
+    pub height: u32,
+    /// Windowed, borderless or fullscreen
+    pub display_mode: DisplayMode,
+    /// Index of the monitor to use among the connected ones, the primary monitor when `None`
+    pub monitor: Option<usize>,
+    /// Number of screen refreshes to wait for before swapping buffers, 0 turns vsync off
+    pub swap_interval: u32,
+    /// Number of samples per pixel for multisample anti aliasing, off when `None`
+    pub samples: Option<u32>,
+    /// The OpenGL version requested as (major, minor)
+    pub gl_version: (u32, u32),
+    /// The OpenGL profile requested
+    pub gl_profile: GlProfile,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings {
+            width: SCR_WIDTH,
+            height: SCR_HEIGHT,
+            display_mode: DisplayMode::Windowed,
+            monitor: None,
+            swap_interval: 1,
+            samples: None,
+            gl_version: (3, 3),
+            gl_profile: GlProfile::Core,
+        }
+    }
+}
+
+/// Options applied to every exercise run through the shared render loop
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    /// Where the exercise renders to
+    pub backend: Backend,
+    /// Size, placement and GL context of the window
+    pub window: WindowSettings,
+    /// Capture the framebuffer after some frames and then stop
+    pub screenshot: Option<ScheduledScreenshot>,
+    /// Advance time by exactly this many seconds per frame instead of following the wall clock
+    pub fixed_time_step: Option<f64>,
+    /// Stop after rendering this many frames
+    pub frame_limit: Option<u32>,
+    /// Stop once this many seconds have passed on the context's clock
+    pub duration_limit: Option<f64>,
+    /// `(name, value)` overrides for the exercise parameters, as given with `--param`
+    pub params: Vec<(String, String)>,
+    /// Directory the exercises load shaders and textures from, the working directory when empty
+    pub asset_root: PathBuf,
+    /// Speed and sensitivity of the fly camera
+    pub camera: CameraSettings,
+    /// Keys for the camera and the shared handling
+    pub bindings: KeyBindings,
+}
+
+/// Parses an OpenGL version written as `MAJOR.MINOR`, e.g. `4.5`
+pub fn parse_gl_version(value: &str) -> std::result::Result<(u32, u32), String> {
+    let (major, minor) = value.split_once('.').ok_or_else(|| format!("expected MAJOR.MINOR, e.g. 3.3, got \"{value}\""))?;
+    let major = major.parse().map_err(|_| format!("major version \"{major}\" is not a number"))?;
+    let minor = minor.parse().map_err(|_| format!("minor version \"{minor}\" is not a number"))?;
+    Ok((major, minor))
+}
+
+/// Parses a number of seconds for the run budget or the time step, anything but a finite positive number would
+/// keep the run from ever ending
+pub fn parse_seconds(value: &str) -> std::result::Result<f64, String> {
+    let seconds: f64 = value.parse().map_err(|_| format!("\"{value}\" is not a number"))?;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(format!("expected a positive number of seconds, got {value}"));
+    }
+    Ok(seconds)
+}
+
+/// The keys the shared handling and the camera exercises respond to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    /// Moves the camera forward while held
+    pub forward: Key,
+    /// Moves the camera backward while held
+    pub backward: Key,
+    /// Moves the camera left while held
+    pub left: Key,
+    /// Moves the camera right while held
+    pub right: Key,
+    /// Closes the window, or goes back to the menu in the launcher
+    pub quit: Key,
+    /// Saves a screenshot of the current frame
+    pub screenshot: Key,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            forward: Key::W,
+            backward: Key::S,
+            left: Key::A,
+            right: Key::D,
+            quit: Key::Escape,
+            screenshot: Key::F12,
+        }
+    }
+}
+
+/// Hooks an exercise implements to be driven by the shared window and render loop
+pub trait App {
+    /// Called once after the GL context is current and the function pointers are loaded.
+    /// An error here (a missing asset, a shader that doesn't compile) aborts the run
+    fn setup(&mut self, ctx: &mut Context) -> Result<()>;
+
+    /// Called once per frame before rendering with the time since the last frame in seconds
+    fn update(&mut self, _ctx: &mut Context, _delta_time: f32) {}
+
+    /// Draws the current frame
+    fn render(&mut self, ctx: &mut Context);
+
+    /// Called for every window event after the default handling (viewport resize, escape to close, F12 screenshot)
+    fn on_event(&mut self, _ctx: &mut Context, _event: &glfw::WindowEvent) {}
+
+    /// Called once after the render loop exits, or after `setup` fails, while the context is still current
+    fn teardown(&mut self, _ctx: &mut Context) {}
+}
+
+thread_local! {
+    /// How many `Context`s are alive on this thread, the latest one created is current
+    static LIVE_CONTEXTS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Whether a `Context` is alive on this thread, so GL calls still reach a context. GL objects that delete
+/// themselves when dropped check this, an app dropped after its context is gone can't delete anything
+pub fn context_current() -> bool {
+    LIVE_CONTEXTS.with(|count| count.get() > 0)
+}
+
+/// The render target behind a `Context`
+enum Surface {
+    Window {
+        glfw: glfw::Glfw,
+        window: glfw::Window,
+        events: Receiver<(f64, glfw::WindowEvent)>,
+    },
+    Headless {
+        headless: Box<Headless>,
+        should_close: bool,
+    },
+}
+
+/// Owns the window (or offscreen framebuffer) and GL context the exercises render into
+pub struct Context {
+    surface: Surface,
+    title: String,
+    screenshot_requested: bool,
+    clock: Box<dyn Clock>,
+    frame: u32,
+    last_frame_time: f64,
+    param_overrides: Vec<(String, String)>,
+    asset_root: PathBuf,
+    camera: CameraSettings,
+    bindings: KeyBindings,
+}
+
+impl Context {
+    /// Creates the GL context for the requested backend and loads the GL function pointers
+    ///
+    /// # Arguments
+    ///
+    /// * `title` the title of the created window
+    /// * `settings` the options selecting and configuring the backend
+    pub fn new(title: &str, settings: &Settings) -> Result<Self> {
+        let surface = match settings.backend {
+            Backend::Window => Self::create_window(title, &settings.window)?,
+            Backend::Headless => Surface::Headless {
+                headless: Box::new(Headless::new(&settings.window)?),
+                should_close: false,
+            },
+        };
+
+        let mut ctx = Context {
+            surface,
+            title: title.to_string(),
+            screenshot_requested: false,
+            clock: match settings.fixed_time_step {
+                Some(step) => Box::new(FixedClock::new(step)),
+                None => Box::new(RealTimeClock::new()),
+            },
+            frame: 0,
+            last_frame_time: 0.0,
+            param_overrides: settings.params.clone(),
+            asset_root: settings.asset_root.clone(),
+            camera: settings.camera,
+            bindings: settings.bindings,
+        };
+        ctx.last_frame_time = ctx.time();
+        LIVE_CONTEXTS.with(|count| count.set(count.get() + 1));
+        Ok(ctx)
+    }
+
+    /// Initializes glfw, creates a window with the requested context and loads the GL function pointers
+    fn create_window(title: &str, settings: &WindowSettings) -> Result<Surface> {
+        // glfw: initialize and configure
+        let mut glfw = glfw::init(glfw::LOG_ERRORS).map_err(|e| Error::WindowCreation(e.to_string()))?;
+        let (major, minor) = settings.gl_version;
+        glfw.window_hint(glfw::WindowHint::ContextVersion(major, minor));
+        glfw.window_hint(glfw::WindowHint::OpenGlProfile(match settings.gl_profile {
+            GlProfile::Core => glfw::OpenGlProfileHint::Core,
+            GlProfile::Compatibility => glfw::OpenGlProfileHint::Compat,
+        }));
+        #[cfg(target_os = "macos")]
+        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+        glfw.window_hint(glfw::WindowHint::Samples(settings.samples));
+
+        // glfw window creation
+        let created = glfw.with_connected_monitors(|glfw, monitors| {
+            // the primary monitor is always listed first
+            let monitor = match settings.monitor {
+                Some(index) => Some(monitors.get(index).ok_or_else(|| {
+                    Error::WindowCreation(format!("there is no monitor {index}, {} connected", monitors.len()))
+                })?),
+                None => monitors.first(),
+            };
+
+            match (settings.display_mode, monitor) {
+                (DisplayMode::Windowed, _) => {
+                    let mut created = glfw.create_window(settings.width, settings.height, title, glfw::WindowMode::Windowed);
+                    if let (Some((window, _)), Some(monitor)) = (&mut created, monitor.filter(|_| settings.monitor.is_some())) {
+                        center_on(window, monitor);
+                    }
+                    Ok(created)
+                }
+                (DisplayMode::Fullscreen, Some(monitor)) => {
+                    Ok(glfw.create_window(settings.width, settings.height, title, glfw::WindowMode::FullScreen(monitor)))
+                }
+                (DisplayMode::Borderless, Some(monitor)) => {
+                    // "windowed full screen": a fullscreen window matching the current video mode needs no mode switch
+                    let mode = monitor.get_video_mode().ok_or_else(|| Error::WindowCreation("the monitor has no video mode".to_string()))?;
+                    glfw.window_hint(glfw::WindowHint::RedBits(Some(mode.red_bits)));
+                    glfw.window_hint(glfw::WindowHint::GreenBits(Some(mode.green_bits)));
+                    glfw.window_hint(glfw::WindowHint::BlueBits(Some(mode.blue_bits)));
+                    glfw.window_hint(glfw::WindowHint::RefreshRate(Some(mode.refresh_rate)));
+                    Ok(glfw.create_window(mode.width, mode.height, title, glfw::WindowMode::FullScreen(monitor)))
+                }
+                (_, None) => Err(Error::WindowCreation("no monitor is connected to go fullscreen on".to_string())),
+            }
+        })?;
+        let (mut window, events) = created.ok_or_else(|| {
+            let profile = match settings.gl_profile {
+                GlProfile::Core => "core",
+                GlProfile::Compatibility => "compatibility",
+            };
+            Error::WindowCreation(format!("glfw could not create a window with an OpenGL {major}.{minor} {profile} context"))
+        })?;
+
+        window.make_current();
+        glfw.set_swap_interval(match settings.swap_interval {
+            0 => glfw::SwapInterval::None,
+            interval => glfw::SwapInterval::Sync(interval),
+        });
+        window.set_key_polling(true);
+        window.set_framebuffer_size_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_scroll_polling(true);
+
+        // gl: load all OpenGL function pointers
+        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+        if !gl::Viewport::is_loaded() {
+            return Err(Error::GlLoad("no OpenGL functions could be loaded through glfw".to_string()));
+        }
+
+        Ok(Surface::Window { glfw, window, events })
+    }
+
+    /// Number of frames rendered so far
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Whether the context renders offscreen with no window
+    pub fn is_headless(&self) -> bool {
+        matches!(self.surface, Surface::Headless { .. })
+    }
+
+    /// Seconds elapsed on the context's clock
+    pub fn time(&self) -> f64 {
+        self.clock.now()
+    }
+
+    /// Replaces the clock driving `time` and the per frame delta time, e.g. with a manual `FixedClock`
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.last_frame_time = clock.now();
+        self.clock = clock;
+    }
+
+    /// The `--param` overrides as (name, value) pairs in the order they were given
+    pub fn param_overrides(&self) -> &[(String, String)] {
+        &self.param_overrides
+    }
+
+    /// The exercise's parameters with the `--param` overrides applied, called by the exercise at setup
+    pub fn params(&self, specs: &[ParamSpec]) -> Result<Params> {
+        Params::resolve(specs, &self.param_overrides)
+    }
+
+    /// Resolves a path relative to the asset root, absolute paths are kept as they are
+    pub fn asset_path(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.asset_root.join(path)
+    }
+
+    /// Speed and sensitivity for the exercises with a fly camera
+    pub fn camera_settings(&self) -> &CameraSettings {
+        &self.camera
+    }
+
+    /// The configured keys
+    pub fn bindings(&self) -> &KeyBindings {
+        &self.bindings
+    }
+
+    /// Whether the key is currently held down. Always false without a window
+    pub fn key_pressed(&self, key: Key) -> bool {
+        match &self.surface {
+            Surface::Window { window, .. } => window.get_key(key) == Action::Press,
+            Surface::Headless { .. } => false,
+        }
+    }
+
+    /// Whether the render loop will stop after the current frame
+    pub fn should_close(&self) -> bool {
+        match &self.surface {
+            Surface::Window { window, .. } => window.should_close(),
+            Surface::Headless { should_close, .. } => *should_close,
+        }
+    }
+
+    /// Requests the render loop to stop (or keep running) after the current frame
+    pub fn set_should_close(&mut self, value: bool) {
+        match &mut self.surface {
+            Surface::Window { window, .. } => window.set_should_close(value),
+            Surface::Headless { should_close, .. } => *should_close = value,
+        }
+    }
+
+    /// Changes the window title, also used to name screenshots taken with F12
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+        if let Surface::Window { window, .. } = &mut self.surface {
+            window.set_title(title);
+        }
+    }
+
+    /// Puts back the GL state the exercises change so the next one starts from a clean slate.
+    ///
+    /// Objects are not deleted here, that is up to each `App::teardown`.
+    pub fn reset_gl_state(&mut self) {
+        self.capture_cursor(false);
+
+        let (width, height) = self.framebuffer_size();
+        unsafe {
+            gl::UseProgram(0);
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.default_framebuffer());
+
+            let mut texture_units = 0;
+            gl::GetIntegerv(gl::MAX_COMBINED_TEXTURE_IMAGE_UNITS, &mut texture_units);
+            for unit in 0..texture_units.min(16) as u32 {
+                gl::ActiveTexture(gl::TEXTURE0 + unit);
+                gl::BindTexture(gl::TEXTURE_2D, 0);
+            }
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Disable(gl::BLEND);
+            gl::Disable(gl::CULL_FACE);
+            gl::Disable(gl::STENCIL_TEST);
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+            gl::Viewport(0, 0, width, height);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+        }
+    }
+
+    /// Hides and locks the cursor to the window, as used for mouse look
+    pub fn capture_cursor(&mut self, captured: bool) {
+        if let Surface::Window { window, .. } = &mut self.surface {
+            let mode = if captured { glfw::CursorMode::Disabled } else { glfw::CursorMode::Normal };
+            window.set_cursor_mode(mode);
+        }
+    }
+
+    /// The size of the framebuffer in pixels
+    pub fn framebuffer_size(&self) -> (i32, i32) {
+        match &self.surface {
+            Surface::Window { window, .. } => window.get_framebuffer_size(),
+            Surface::Headless { headless, .. } => {
+                let (width, height) = headless.size();
+                (width as i32, height as i32)
+            }
+        }
+    }
+
+    /// The framebuffer object that is drawn to when nothing else is bound (0 for a window)
+    pub fn default_framebuffer(&self) -> gl::types::GLuint {
+        match &self.surface {
+            Surface::Window { .. } => 0,
+            Surface::Headless { headless, .. } => headless.framebuffer(),
+        }
+    }
+
+    /// Width over height of the framebuffer, for building projection matrices
+    pub fn aspect_ratio(&self) -> f32 {
+        let (width, height) = self.framebuffer_size();
+        width as f32 / height.max(1) as f32
+    }
+
+    /// Reads the current contents of the default framebuffer
+    pub fn capture(&self) -> image::RgbImage {
+        let (width, height) = self.framebuffer_size();
+        let framebuffer = match &self.surface {
+            // a multisampled window framebuffer is resolved by glReadPixels itself
+            Surface::Window { .. } => 0,
+            Surface::Headless { headless, .. } => headless.resolve(),
+        };
+        screenshot::capture(framebuffer, width as u32, height as u32)
+    }
+
+    /// Captures the default framebuffer and writes it to `path`, reporting the outcome on the console
+    pub fn save_screenshot(&self, path: &std::path::Path) {
+        match screenshot::save(&self.capture(), path) {
+            Ok(_) => println!("Saved screenshot to {}", path.display()),
+            Err(e) => eprintln!("Failed to save screenshot to {}: {e}", path.display()),
+        }
+    }
+
+    /// Takes the window events received since the last call
+    fn poll_events(&mut self) -> Vec<glfw::WindowEvent> {
+        match &mut self.surface {
+            Surface::Window { glfw, events, .. } => {
+                glfw.poll_events();
+                glfw::flush_messages(events).map(|(_, event)| event).collect()
+            }
+            Surface::Headless { .. } => Vec::new(),
+        }
+    }
+
+    /// Presents the rendered frame
+    fn swap_buffers(&mut self) {
+        match &mut self.surface {
+            Surface::Window { window, .. } => window.swap_buffers(),
+            Surface::Headless { .. } => unsafe { gl::Flush() },
+        }
+    }
+
+    /// Runs one iteration of the render loop up to (not including) presenting the frame
+    fn render_frame(&mut self, app: &mut dyn App, settings: &Settings) {
+        let current_frame_time = self.time();
+        let delta_time = (current_frame_time - self.last_frame_time) as f32;
+        self.last_frame_time = current_frame_time;
+
+        // events
+        for event in self.poll_events() {
+            self.handle_event(&event);
+            app.on_event(self, &event);
+        }
+
+        app.update(self, delta_time);
+        app.render(self);
+        self.frame += 1;
+        self.clock.tick();
+
+        // read back before swapping, the back buffer is undefined afterwards
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+            self.save_screenshot(&screenshot::timestamped_path(&self.title, self.frame));
+        }
+        if let Some(scheduled) = &settings.screenshot {
+            if self.frame == scheduled.after_frames {
+                self.save_screenshot(&scheduled.path);
+                self.set_should_close(true);
+            }
+        }
+
+        // stop once the run has used up its budget
+        if settings.frame_limit.is_some_and(|limit| self.frame >= limit)
+            || settings.duration_limit.is_some_and(|limit| self.time() >= limit) {
+            self.set_should_close(true);
+        }
+    }
+
+    /// Handling shared by every exercise, applied before the event reaches the `App`
+    fn handle_event(&mut self, event: &glfw::WindowEvent) {
+        match *event {
+            glfw::WindowEvent::FramebufferSize(width, height) => {
+                // make sure the viewport matches the new window dimensions.
+                // note that the width and height will be significantly larger than specified on retina displays
+                unsafe { gl::Viewport(0, 0, width, height) }
+            }
+            glfw::WindowEvent::Key(key, _, Action::Press, _) if key == self.bindings.quit => self.set_should_close(true),
+            glfw::WindowEvent::Key(key, _, Action::Press, _) if key == self.bindings.screenshot => self.screenshot_requested = true,
+            _ => {}
+        }
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        LIVE_CONTEXTS.with(|count| count.set(count.get() - 1));
+    }
+}
+
+/// Moves a window to the middle of a monitor's work area
+fn center_on(window: &mut glfw::Window, monitor: &glfw::Monitor) {
+    let (x, y, width, height) = monitor.get_workarea();
+    let (window_width, window_height) = window.get_size();
+    window.set_pos(x + (width - window_width) / 2, y + (height - window_height) / 2);
+}
+
+/// Calls `app.setup`, tearing the app down when it fails so whatever it created is released while the context is
+/// still current
+///
+/// # Arguments
+///
+/// * `app` the exercise to set up
+/// * `ctx` the context the exercise renders into
+pub(crate) fn setup(app: &mut dyn App, ctx: &mut Context) -> Result<()> {
+    let result = app.setup(ctx);
+    if result.is_err() {
+        app.teardown(ctx);
+    }
+    result
+}
+
+/// Creates a window (or offscreen framebuffer) and drives the `App` hooks until it is closed
+///
+/// # Arguments
+///
+/// * `title` the title of the created window
+/// * `app` the exercise to run
+/// * `settings` the options applied to the run
+pub fn run(title: &str, app: &mut dyn App, settings: &Settings) -> Result<()> {
+    let mut ctx = Context::new(title, settings)?;
+    setup(app, &mut ctx)?;
+
+    // render loop
+    while !ctx.should_close() {
+        ctx.render_frame(app, settings);
+        ctx.swap_buffers();
+    }
+
+    app.teardown(&mut ctx);
+    Ok(())
+}
+
+/// Renders a fixed number of frames and returns the contents of the last one
+///
+/// # Arguments
+///
+/// * `title` the title of the created window
+/// * `app` the exercise to run
+/// * `settings` the options applied to the run, usually headless with a fixed time step
+/// * `frames` how many frames to render, at least one
+pub fn render_frames(title: &str, app: &mut dyn App, settings: &Settings, frames: u32) -> Result<image::RgbImage> {
+    let mut ctx = Context::new(title, settings)?;
+    setup(app, &mut ctx)?;
+
+    for _ in 1..frames {
+        ctx.render_frame(app, settings);
+        ctx.swap_buffers();
+    }
+    ctx.render_frame(app, settings);
+    let image = ctx.capture();
+
+    app.teardown(&mut ctx);
+    Ok(image)
+}
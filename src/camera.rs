@@ -22,6 +22,24 @@ const SPEED: f32 = 2.5;
 const SENSITIVITY: f32 = 0.1;
 const ZOOM: f32 = 45.0;
 
+/// The camera options that can be changed from `learn_opengl.toml`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraSettings {
+    /// Distance moved per second while a movement key is held
+    pub movement_speed: f32,
+    /// Degrees turned per pixel of mouse movement
+    pub mouse_sensitivity: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        CameraSettings {
+            movement_speed: SPEED,
+            mouse_sensitivity: SENSITIVITY,
+        }
+    }
+}
+
 pub struct Camera {
     // Camera Atributes
     position: Point3,
@@ -75,6 +93,12 @@ impl Camera {
         }
     }
 
+    /// replaces the movement speed and mouse sensitivity
+    pub fn apply_settings(&mut self, settings: &CameraSettings) {
+        self.movement_speed = settings.movement_speed;
+        self.mouse_sensitivity = settings.mouse_sensitivity;
+    }
+
     /// Returns the view matrix calculated using Euler Angles and the look at matrix
     pub fn get_view_matrix(&self) -> Matrix4 {
         Matrix4::look_at_rh(self.position, self.position + self.front, self.up)
@@ -0,0 +1,74 @@
+use std::time::Instant;
+
+/// Source of time for the render loop, so animations can be replayed exactly
+pub trait Clock {
+    /// Seconds since the clock started
+    fn now(&self) -> f64;
+
+    /// Called by the render loop once every frame has been rendered
+    fn tick(&mut self) {}
+}
+
+/// Follows the wall clock
+pub struct RealTimeClock {
+    start: Instant,
+}
+
+impl RealTimeClock {
+    /// Starts counting from now
+    pub fn new() -> Self {
+        RealTimeClock { start: Instant::now() }
+    }
+}
+
+impl Default for RealTimeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealTimeClock {
+    fn now(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+}
+
+/// Advances by the same step every frame no matter how long the frame took.
+///
+/// A step of zero gives a manual clock that only moves through `set` and `advance`.
+pub struct FixedClock {
+    step: f64,
+    elapsed: f64,
+}
+
+impl FixedClock {
+    /// Starts at zero and advances `step` seconds per frame
+    pub fn new(step: f64) -> Self {
+        FixedClock { step, elapsed: 0.0 }
+    }
+
+    /// A clock that stays put until moved by hand
+    pub fn manual() -> Self {
+        Self::new(0.0)
+    }
+
+    /// Jumps to `seconds`
+    pub fn set(&mut self, seconds: f64) {
+        self.elapsed = seconds;
+    }
+
+    /// Moves forward by `seconds` on top of the per frame step
+    pub fn advance(&mut self, seconds: f64) {
+        self.elapsed += seconds;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> f64 {
+        self.elapsed
+    }
+
+    fn tick(&mut self) {
+        self.elapsed += self.step;
+    }
+}
@@ -1,84 +1,86 @@
 use std::ffi::c_void;
 use std::path::Path;
-use std::sync::mpsc::Receiver;
-use glfw::Action;
+use image::DynamicImage;
 use image::DynamicImage::{ImageLuma8, ImageLumaA8, ImageRgb8, ImageRgba8};
+use crate::app::Context;
 use crate::camera::Camera;
 use crate::camera::CameraMovement::{BACKWARD, FORWARD, LEFT, RIGHT};
+use crate::error::{Error, Result};
 
 /// Event processing function use for the camera class and later tutorials
-pub fn process_events(
-    events: &Receiver<(f64, glfw::WindowEvent)>,
+pub fn process_event(
+    event: &glfw::WindowEvent,
     first_mouse: &mut bool,
     last_x: &mut f32,
     last_y: &mut f32,
     camera: &mut Camera,
 ) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            glfw::WindowEvent::FramebufferSize(width, height) => {
-                unsafe { gl::Viewport(0, 0, width, height) }
+    match *event {
+        glfw::WindowEvent::CursorPos(xpos, ypos) => {
+            let (xpos, ypos) = (xpos as f32, ypos as f32);
+            if *first_mouse {
+                *last_x = xpos;
+                *last_y = ypos;
+                *first_mouse = false;
             }
-            glfw::WindowEvent::CursorPos(xpos, ypos) => {
-                let (xpos, ypos) = (xpos as f32, ypos as f32);
-                if *first_mouse {
-                    *last_x = xpos;
-                    *last_y = ypos;
-                    *first_mouse = false;
-                }
 
-                let xoffset = xpos - *last_x;
-                let yoffset = *last_y - ypos; // reverse since y-coordinates go from bottom to top
+            let xoffset = xpos - *last_x;
+            let yoffset = *last_y - ypos; // reverse since y-coordinates go from bottom to top
 
-                *last_x = xpos;
-                *last_y = ypos;
+            *last_x = xpos;
+            *last_y = ypos;
 
-                camera.process_mouse_movement(xoffset, yoffset, true);
-            }
-            glfw::WindowEvent::Scroll(_xoffset, yoffset) => {
-                camera.process_mouse_scroll(yoffset as f32);
-            }
-            _ => {}
+            camera.process_mouse_movement(xoffset, yoffset, true);
+        }
+        glfw::WindowEvent::Scroll(_xoffset, yoffset) => {
+            camera.process_mouse_scroll(yoffset as f32);
         }
+        _ => {}
     }
 }
 
 
 /// Input processing function as introduced for camera
-pub fn process_input(window: &mut glfw::Window, delta_time: f32, camera: &mut Camera) {
-    if window.get_key(glfw::Key::Escape) == Action::Press {
-        window.set_should_close(true)
-    }
-
-    if window.get_key(glfw::Key::W) == Action::Press {
+pub fn process_input(ctx: &Context, delta_time: f32, camera: &mut Camera) {
+    let bindings = ctx.bindings();
+    if ctx.key_pressed(bindings.forward) {
         camera.process_keyboard(FORWARD, delta_time);
     }
-    if window.get_key(glfw::Key::S) == Action::Press {
+    if ctx.key_pressed(bindings.backward) {
         camera.process_keyboard(BACKWARD, delta_time);
     }
-    if window.get_key(glfw::Key::A) == Action::Press {
+    if ctx.key_pressed(bindings.left) {
         camera.process_keyboard(LEFT, delta_time);
     }
-    if window.get_key(glfw::Key::D) == Action::Press {
+    if ctx.key_pressed(bindings.right) {
         camera.process_keyboard(RIGHT, delta_time);
     }
 }
 
+/// Opens an image file, telling a missing file apart from one that can't be decoded
+pub fn load_image(path: &Path) -> Result<DynamicImage> {
+    image::open(path).map_err(|e| match e {
+        image::ImageError::IoError(source) => Error::AssetIo { path: path.to_path_buf(), source },
+        e => Error::ImageDecode { path: path.to_path_buf(), reason: e.to_string() },
+    })
+}
+
 /// utility function for loading a 2D texture from file
 #[allow(dead_code)]
-pub fn load_texture(path: &str) -> u32 {
+pub fn load_texture(path: &str) -> Result<u32> {
+    let img = load_image(Path::new(path))?;
+    let format = match img {
+        ImageLuma8(_) => gl::RED,
+        ImageLumaA8(_) => gl::RG,
+        ImageRgb8(_) => gl::RGB,
+        ImageRgba8(_) => gl::RGBA,
+        _ => return Err(Error::ImageDecode { path: path.into(), reason: "unsupported pixel format".to_string() }),
+    };
+
     let mut texture_id = 0;
     unsafe {
 
         gl::GenTextures(1, &mut texture_id);
-        let img = image::open(Path::new(path)).expect("Texture failed to load");
-        let format = match img {
-            ImageLuma8(_) => gl::RED,
-            ImageLumaA8(_) => gl::RG,
-            ImageRgb8(_) => gl::RGB,
-            ImageRgba8(_) => gl::RGBA,
-            _ => { panic!("invalid image format in: {path}") }
-        };
 
         let data = img.as_bytes();
 
@@ -100,6 +102,5 @@ pub fn load_texture(path: &str) -> u32 {
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
 
     }
-    texture_id
+    Ok(texture_id)
 }
-
@@ -0,0 +1,312 @@
+use gl::types::{GLenum, GLint, GLuint};
+use glfw::{Action, Key};
+use crate::app::{App, Context};
+use crate::error::Result;
+use crate::lister::{Lister, RunID};
+use crate::shader::Shader;
+
+/// How the two exercises share the window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompareMode {
+    /// Both exercises scaled to half the window next to each other
+    #[default]
+    SideBySide,
+    /// Both exercises at full size, the first left of a movable divider and the second right of it
+    Wipe,
+}
+
+/// Capabilities tracked per exercise, since exercises enable them once in `setup` and expect them to stay on
+const CAPABILITIES: [GLenum; 4] = [gl::DEPTH_TEST, gl::BLEND, gl::CULL_FACE, gl::STENCIL_TEST];
+
+/// How far Left and Right move the wipe divider, as a fraction of the window width
+const DIVIDER_STEP: f32 = 0.02;
+
+/// Width of the wipe divider line in pixels
+const DIVIDER_WIDTH: i32 = 2;
+
+/// Draws a texture over the whole viewport, the quad comes from the vertex ids so no buffers are needed
+const COMPOSITE_VERTEX_SHADER: &str = r#"
+    #version 330 core
+    out vec2 TexCoord;
+    void main() {
+        TexCoord = vec2(gl_VertexID & 1, gl_VertexID >> 1);
+        gl_Position = vec4(TexCoord * 2.0 - 1.0, 0.0, 1.0);
+    }
+"#;
+
+const COMPOSITE_FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    out vec4 FragColor;
+    in vec2 TexCoord;
+    uniform sampler2D image;
+    void main() {
+        FragColor = texture(image, TexCoord);
+    }
+"#;
+
+/// A framebuffer with a color texture and a depth and stencil buffer, standing in for the window for one exercise
+#[derive(Default)]
+struct RenderTarget {
+    framebuffer: GLuint,
+    color: GLuint,
+    depth_stencil: GLuint,
+}
+
+impl RenderTarget {
+    fn new((width, height): (i32, i32)) -> Self {
+        let mut target = RenderTarget::default();
+        unsafe {
+            gl::GenTextures(1, &mut target.color);
+            gl::BindTexture(gl::TEXTURE_2D, target.color);
+            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::RGBA8 as GLint, width, height, 0, gl::RGBA, gl::UNSIGNED_BYTE, std::ptr::null());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::GenRenderbuffers(1, &mut target.depth_stencil);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, target.depth_stencil);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH24_STENCIL8, width, height);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+            gl::GenFramebuffers(1, &mut target.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.framebuffer);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, target.color, 0);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::RENDERBUFFER, target.depth_stencil);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+        }
+        target
+    }
+
+    fn delete(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteRenderbuffers(1, &self.depth_stencil);
+            gl::DeleteTextures(1, &self.color);
+        }
+        *self = RenderTarget::default();
+    }
+}
+
+/// One of the two exercises with where it renders to and the capabilities it left enabled
+struct Side {
+    label: String,
+    app: Box<dyn App>,
+    target: RenderTarget,
+    enabled: [bool; CAPABILITIES.len()],
+    /// Whether `setup` was called on the exercise and `teardown` wasn't yet
+    set_up: bool,
+}
+
+impl Side {
+    /// Makes the exercise's framebuffer and capabilities current before calling one of its hooks
+    fn activate(&self, ctx: &Context) {
+        let (width, height) = ctx.framebuffer_size();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.target.framebuffer);
+            gl::Viewport(0, 0, width, height);
+        }
+        set_capabilities(&self.enabled);
+    }
+
+    /// Remembers the capabilities after one of the exercise's hooks
+    fn deactivate(&mut self) {
+        self.enabled = CAPABILITIES.map(|capability| unsafe { gl::IsEnabled(capability) == gl::TRUE });
+    }
+}
+
+/// Runs two exercises, or two variants of one, in the same window to compare them.
+///
+/// Both get every event and the same frame times, so exercises with a camera move in lockstep. Each renders into
+/// a framebuffer of its own that is then shown side by side or split by a divider that Left and Right move.
+/// Tab switches between the two layouts. Exercises must draw to the framebuffer bound when their hooks are called
+/// rather than binding framebuffer 0.
+pub struct Compare {
+    sides: [Side; 2],
+    mode: CompareMode,
+    /// Position of the wipe divider as a fraction of the window width
+    divider: f32,
+    composite: Option<Shader>,
+    /// Empty vertex array, core profile needs one bound to draw
+    vao: GLuint,
+}
+
+impl Compare {
+    /// Creates the comparison of two runs from `lister`
+    ///
+    /// # Arguments
+    ///
+    /// * `lister` where to find the runs
+    /// * `left` the run shown on the left
+    /// * `right` the run shown on the right
+    /// * `mode` the layout to start with
+    /// * `overrides` the `--param` overrides, both runs must have a parameter of each name
+    pub fn new(lister: &Lister, left: &RunID, right: &RunID, mode: CompareMode, overrides: &[(String, String)]) -> Result<Self> {
+        let side = |id: &RunID| -> Result<Side> {
+            Ok(Side { label: lister.label(id)?, app: lister.create_app(id, overrides)?, target: RenderTarget::default(), enabled: [false; CAPABILITIES.len()], set_up: false })
+        };
+        Ok(Compare { sides: [side(left)?, side(right)?], mode, divider: 0.5, composite: None, vao: 0 })
+    }
+
+    /// The window title naming both sides
+    pub fn title(&self) -> String {
+        format!("{} | {}", self.sides[0].label, self.sides[1].label)
+    }
+
+    fn render_side_by_side(&self, width: i32, height: i32) {
+        // each side keeps the window's aspect ratio at half its size, centered vertically
+        let (half_width, half_height) = (width / 2, height / 2);
+        for (index, side) in self.sides.iter().enumerate() {
+            unsafe {
+                gl::Viewport(index as i32 * half_width, (height - half_height) / 2, half_width, half_height);
+                gl::BindTexture(gl::TEXTURE_2D, side.target.color);
+                gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+            }
+        }
+    }
+
+    fn render_wipe(&self, width: i32, height: i32) {
+        let divider = (self.divider * width as f32).round() as i32;
+        unsafe {
+            gl::Viewport(0, 0, width, height);
+            gl::Enable(gl::SCISSOR_TEST);
+            for (side, (x, scissor_width)) in self.sides.iter().zip([(0, divider), (divider, width - divider)]) {
+                gl::Scissor(x, 0, scissor_width, height);
+                gl::BindTexture(gl::TEXTURE_2D, side.target.color);
+                gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+            }
+
+            gl::Scissor(divider - DIVIDER_WIDTH / 2, 0, DIVIDER_WIDTH, height);
+            gl::ClearColor(1.0, 1.0, 1.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+    }
+
+    fn recreate_targets(&mut self, ctx: &Context) {
+        for side in &mut self.sides {
+            side.target.delete();
+            side.target = RenderTarget::new(ctx.framebuffer_size());
+        }
+    }
+}
+
+impl App for Compare {
+    fn setup(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_title(&self.title());
+        println!("Tab switches between side by side and wipe, Left and Right move the wipe divider");
+
+        self.composite = Some(Shader::from_source(COMPOSITE_VERTEX_SHADER, COMPOSITE_FRAGMENT_SHADER)?);
+        unsafe { gl::GenVertexArrays(1, &mut self.vao) };
+        self.recreate_targets(ctx);
+
+        for side in &mut self.sides {
+            side.activate(ctx);
+            side.set_up = true;
+            let result = side.app.setup(ctx);
+            side.deactivate();
+            if let Err(e) = result {
+                // the render loop won't run, release both sides and everything shared between them
+                self.teardown(ctx);
+                return Err(e);
+            }
+        }
+        set_capabilities(&[false; CAPABILITIES.len()]);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut Context, delta_time: f32) {
+        for side in &mut self.sides {
+            side.activate(ctx);
+            side.app.update(ctx, delta_time);
+            side.deactivate();
+        }
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        for side in &mut self.sides {
+            side.activate(ctx);
+            side.app.render(ctx);
+            side.deactivate();
+        }
+
+        let (width, height) = ctx.framebuffer_size();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, ctx.default_framebuffer());
+            set_capabilities(&[false; CAPABILITIES.len()]);
+            gl::Viewport(0, 0, width, height);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            self.composite.as_ref().unwrap().use_program();
+            gl::BindVertexArray(self.vao);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        match self.mode {
+            CompareMode::SideBySide => self.render_side_by_side(width, height),
+            CompareMode::Wipe => self.render_wipe(width, height),
+        }
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    fn on_event(&mut self, ctx: &mut Context, event: &glfw::WindowEvent) {
+        match *event {
+            glfw::WindowEvent::Key(Key::Tab, _, Action::Press, _) => {
+                self.mode = match self.mode {
+                    CompareMode::SideBySide => CompareMode::Wipe,
+                    CompareMode::Wipe => CompareMode::SideBySide,
+                };
+                return;
+            }
+            glfw::WindowEvent::Key(Key::Left, _, Action::Press | Action::Repeat, _) if self.mode == CompareMode::Wipe => {
+                self.divider = (self.divider - DIVIDER_STEP).max(0.0);
+                return;
+            }
+            glfw::WindowEvent::Key(Key::Right, _, Action::Press | Action::Repeat, _) if self.mode == CompareMode::Wipe => {
+                self.divider = (self.divider + DIVIDER_STEP).min(1.0);
+                return;
+            }
+            glfw::WindowEvent::FramebufferSize(..) => self.recreate_targets(ctx),
+            _ => {}
+        }
+
+        for side in &mut self.sides {
+            side.activate(ctx);
+            side.app.on_event(ctx, event);
+            side.deactivate();
+        }
+    }
+
+    fn teardown(&mut self, ctx: &mut Context) {
+        // also called by a failed `setup`, only what is still alive is released
+        for side in &mut self.sides {
+            if side.set_up {
+                side.activate(ctx);
+                side.app.teardown(ctx);
+                side.set_up = false;
+            }
+            side.target.delete();
+        }
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, ctx.default_framebuffer());
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+        self.vao = 0;
+        self.composite = None;
+    }
+}
+
+/// Enables or disables each of `CAPABILITIES`
+fn set_capabilities(enabled: &[bool; CAPABILITIES.len()]) {
+    for (capability, enabled) in CAPABILITIES.iter().zip(enabled) {
+        unsafe {
+            if *enabled {
+                gl::Enable(*capability);
+            } else {
+                gl::Disable(*capability);
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use glfw::Key;
+use serde::{Deserialize, Deserializer};
+use crate::app::{self, DisplayMode, GlProfile, Settings};
+use crate::error::{Error, Result};
+use crate::lister::RunID;
+
+/// Name of the configuration file looked for in the working directory and in the user's config directory
+pub const FILE_NAME: &str = "learn_opengl.toml";
+
+/// The contents of `learn_opengl.toml`, every key is optional and falls back to the built in default.
+///
+/// ```toml
+/// default_exercise = "1.8"
+/// asset_root = "."
+///
+/// [window]
+/// width = 1280
+/// height = 720
+/// gl_version = "4.5"
+///
+/// [camera]
+/// speed = 5.0
+/// sensitivity = 0.05
+///
+/// [bindings]
+/// forward = "Up"
+/// backward = "Down"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The exercise run when no CHAPTER.SECTION or NAME is given
+    #[serde(deserialize_with = "parsed")]
+    pub default_exercise: Option<RunID>,
+    /// Directory holding `shaders/` and `textures/`, relative to the file it is set in
+    pub asset_root: Option<PathBuf>,
+    pub window: WindowConfig,
+    pub camera: CameraConfig,
+    pub bindings: BindingsConfig,
+}
+
+/// The `[window]` table, same meaning as the command line flags of the same name
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub display_mode: Option<DisplayMode>,
+    pub monitor: Option<usize>,
+    pub swap_interval: Option<u32>,
+    pub samples: Option<u32>,
+    #[serde(deserialize_with = "gl_version")]
+    pub gl_version: Option<(u32, u32)>,
+    pub gl_profile: Option<GlProfile>,
+}
+
+/// The `[camera]` table
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraConfig {
+    /// Distance moved per second while a movement key is held
+    pub speed: Option<f32>,
+    /// Degrees turned per pixel of mouse movement
+    pub sensitivity: Option<f32>,
+}
+
+/// The `[bindings]` table, keys are named like `W`, `Space`, `Up`, `F12` or `LeftShift`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BindingsConfig {
+    #[serde(deserialize_with = "key")]
+    pub forward: Option<Key>,
+    #[serde(deserialize_with = "key")]
+    pub backward: Option<Key>,
+    #[serde(deserialize_with = "key")]
+    pub left: Option<Key>,
+    #[serde(deserialize_with = "key")]
+    pub right: Option<Key>,
+    #[serde(deserialize_with = "key")]
+    pub quit: Option<Key>,
+    #[serde(deserialize_with = "key")]
+    pub screenshot: Option<Key>,
+}
+
+impl Config {
+    /// Reads the user level file and then the project file, values in the project file win
+    ///
+    /// # Arguments
+    ///
+    /// * `project_file` a file to read instead of `learn_opengl.toml` in the working directory, it must exist
+    pub fn load(project_file: Option<&Path>) -> Result<Config> {
+        let user_file = dirs::config_dir().map(|dir| dir.join("learn_opengl").join(FILE_NAME));
+        if let Some(path) = project_file.filter(|path| !path.is_file()) {
+            return Err(Error::Config { path: path.to_path_buf(), reason: "no such file".to_string() });
+        }
+        let project_file = project_file.map_or_else(|| PathBuf::from(FILE_NAME), Path::to_path_buf);
+
+        Self::load_files(user_file.iter().chain([&project_file]))
+    }
+
+    /// Reads and merges the files that exist, later files overriding earlier ones key by key
+    pub fn load_files<'a>(paths: impl IntoIterator<Item = &'a PathBuf>) -> Result<Config> {
+        let mut merged = toml::Table::new();
+        let mut last_read = None;
+        for path in paths {
+            if let Some(table) = read(path)? {
+                merge(&mut merged, table);
+                last_read = Some(path);
+            }
+        }
+        // files that are fine on their own can still disagree once merged, blame the one read last
+        Config::deserialize(merged).map_err(|e| Error::Config {
+            path: last_read.cloned().unwrap_or_default(),
+            reason: format!("conflicts with the files read before it: {}", e.to_string().trim_end()),
+        })
+    }
+
+    /// The run settings with the configured values in place of the built in defaults
+    pub fn settings(&self) -> Settings {
+        let mut settings = Settings::default();
+        let window = &mut settings.window;
+        window.width = self.window.width.unwrap_or(window.width);
+        window.height = self.window.height.unwrap_or(window.height);
+        window.display_mode = self.window.display_mode.unwrap_or(window.display_mode);
+        window.monitor = self.window.monitor.or(window.monitor);
+        window.swap_interval = self.window.swap_interval.unwrap_or(window.swap_interval);
+        window.samples = self.window.samples.or(window.samples);
+        window.gl_version = self.window.gl_version.unwrap_or(window.gl_version);
+        window.gl_profile = self.window.gl_profile.unwrap_or(window.gl_profile);
+
+        if let Some(asset_root) = &self.asset_root {
+            settings.asset_root = asset_root.clone();
+        }
+
+        let camera = &mut settings.camera;
+        camera.movement_speed = self.camera.speed.unwrap_or(camera.movement_speed);
+        camera.mouse_sensitivity = self.camera.sensitivity.unwrap_or(camera.mouse_sensitivity);
+
+        let bindings = &mut settings.bindings;
+        bindings.forward = self.bindings.forward.unwrap_or(bindings.forward);
+        bindings.backward = self.bindings.backward.unwrap_or(bindings.backward);
+        bindings.left = self.bindings.left.unwrap_or(bindings.left);
+        bindings.right = self.bindings.right.unwrap_or(bindings.right);
+        bindings.quit = self.bindings.quit.unwrap_or(bindings.quit);
+        bindings.screenshot = self.bindings.screenshot.unwrap_or(bindings.screenshot);
+
+        settings
+    }
+}
+
+/// Parses one file, `None` when it doesn't exist
+fn read(path: &Path) -> Result<Option<toml::Table>> {
+    let invalid = |reason: String| Error::Config { path: path.to_path_buf(), reason };
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(invalid(e.to_string())),
+    };
+    // checked on its own first so mistakes are reported against the file and line they are in
+    toml::from_str::<Config>(&text).map_err(|e| invalid(e.to_string().trim_end().to_string()))?;
+    let mut table: toml::Table = text.parse().map_err(|e: toml::de::Error| invalid(e.to_string()))?;
+
+    if let Some(toml::Value::String(asset_root)) = table.get_mut("asset_root") {
+        let base = path.parent().unwrap_or(Path::new(""));
+        *asset_root = base.join(&*asset_root).to_string_lossy().into_owned();
+    }
+    Ok(Some(table))
+}
+
+/// Copies every key of `over` into `base`, merging tables present in both
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(inner)), toml::Value::Table(over)) => merge(inner, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Deserializes a string with its `FromStr` implementation
+fn parsed<'de, D: Deserializer<'de>, T: FromStr>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    T::Err: Display,
+{
+    let text = String::deserialize(deserializer)?;
+    text.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+fn gl_version<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<(u32, u32)>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    app::parse_gl_version(&text).map(Some).map_err(serde::de::Error::custom)
+}
+
+fn key<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<Key>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_key(&name).map(Some).ok_or_else(|| serde::de::Error::custom(format!("unknown key \"{name}\"")))
+}
+
+/// Looks up a key by name ignoring case, spaces, `-` and `_`, e.g. `w`, `Page Up` or `left_shift`
+pub fn parse_key(name: &str) -> Option<Key> {
+    let normalized: String = name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase();
+    KEY_NAMES.iter().find(|(key_name, _)| *key_name == normalized).map(|(_, key)| *key)
+}
+
+/// Names accepted by `parse_key`, already normalized
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("a", Key::A), ("b", Key::B), ("c", Key::C), ("d", Key::D), ("e", Key::E), ("f", Key::F), ("g", Key::G),
+    ("h", Key::H), ("i", Key::I), ("j", Key::J), ("k", Key::K), ("l", Key::L), ("m", Key::M), ("n", Key::N),
+    ("o", Key::O), ("p", Key::P), ("q", Key::Q), ("r", Key::R), ("s", Key::S), ("t", Key::T), ("u", Key::U),
+    ("v", Key::V), ("w", Key::W), ("x", Key::X), ("y", Key::Y), ("z", Key::Z),
+    ("0", Key::Num0), ("1", Key::Num1), ("2", Key::Num2), ("3", Key::Num3), ("4", Key::Num4),
+    ("5", Key::Num5), ("6", Key::Num6), ("7", Key::Num7), ("8", Key::Num8), ("9", Key::Num9),
+    ("f1", Key::F1), ("f2", Key::F2), ("f3", Key::F3), ("f4", Key::F4), ("f5", Key::F5), ("f6", Key::F6),
+    ("f7", Key::F7), ("f8", Key::F8), ("f9", Key::F9), ("f10", Key::F10), ("f11", Key::F11), ("f12", Key::F12),
+    ("up", Key::Up), ("down", Key::Down), ("left", Key::Left), ("right", Key::Right),
+    ("pageup", Key::PageUp), ("pagedown", Key::PageDown), ("home", Key::Home), ("end", Key::End),
+    ("insert", Key::Insert), ("delete", Key::Delete), ("backspace", Key::Backspace),
+    ("space", Key::Space), ("enter", Key::Enter), ("tab", Key::Tab), ("escape", Key::Escape),
+    ("leftshift", Key::LeftShift), ("rightshift", Key::RightShift),
+    ("leftcontrol", Key::LeftControl), ("rightcontrol", Key::RightControl),
+    ("leftalt", Key::LeftAlt), ("rightalt", Key::RightAlt),
+];
@@ -0,0 +1,76 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::shader_error::ShaderError;
+
+/// Everything that can go wrong while launching or running an exercise
+#[derive(Debug)]
+pub enum Error {
+    /// The window (or offscreen context) could not be created
+    WindowCreation(String),
+    /// The OpenGL function pointers could not be loaded
+    GlLoad(String),
+    /// A shader stage failed to compile or a program failed to link
+    Shader(ShaderError),
+    /// An asset file could not be read
+    AssetIo { path: PathBuf, source: std::io::Error },
+    /// An image could not be decoded or is in a format the exercises can't upload
+    ImageDecode { path: PathBuf, reason: String },
+    /// The id given on the command line can't be parsed, `reason` says which part is wrong
+    InvalidId { id: String, reason: String },
+    /// A `--param` names a parameter the exercise doesn't have or its value doesn't parse
+    InvalidParam { name: String, reason: String },
+    /// A configuration file can't be read or holds a key or value that isn't understood
+    Config { path: PathBuf, reason: String },
+    /// No exercise matches the requested id or name, `suggestions` holds the closest names
+    UnknownId { id: String, suggestions: Vec<String> },
+    /// A new exercise would reuse the CHAPTER.SECTION or name of the registered exercise `by`
+    IdTaken { id: String, by: String },
+    /// The requested name is a prefix of several exercises
+    AmbiguousId { id: String, matches: Vec<String> },
+}
+
+/// Result type used throughout the crate
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::WindowCreation(reason) => write!(f, "failed to create the window or GL context: {reason}"),
+            Error::GlLoad(reason) => write!(f, "failed to load OpenGL: {reason}"),
+            Error::Shader(e) => write!(f, "{e}"),
+            Error::AssetIo { path, source } => write!(f, "failed to read {}: {source}", path.display()),
+            Error::ImageDecode { path, reason } => write!(f, "failed to load image {}: {reason}", path.display()),
+            Error::InvalidId { id, reason } => write!(f, "\"{id}\" is not a valid id, {reason}"),
+            Error::InvalidParam { name, reason } => write!(f, "parameter \"{name}\": {reason}"),
+            Error::Config { path, reason } => write!(f, "invalid configuration in {}: {reason}", path.display()),
+            Error::UnknownId { id, suggestions } => {
+                write!(f, "no exercise matches \"{id}\"")?;
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean {}?", quoted_list(suggestions))?;
+                }
+                Ok(())
+            }
+            Error::IdTaken { id, by } => write!(f, "\"{id}\" clashes with the existing exercise \"{by}\""),
+            Error::AmbiguousId { id, matches } => write!(f, "\"{id}\" is ambiguous, it could be {}", quoted_list(matches)),
+        }
+    }
+}
+
+/// `"a", "b" or "c"`
+fn quoted_list(items: &[String]) -> String {
+    let quoted: Vec<String> = items.iter().map(|item| format!("\"{item}\"")).collect();
+    match quoted.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {last}", rest.join(", ")),
+        _ => quoted.join(""),
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::AssetIo { source, .. } => Some(source),
+            Error::Shader(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -1,18 +1,29 @@
 use std::ffi::c_void;
-use std::sync::mpsc::Receiver;
-use glfw::Context;
-use crate::runner::Runner;
+use gl::types::{GLfloat, GLsizei, GLsizeiptr, GLuint};
+use crate::app::{App, Context};
+use crate::error::Result;
+use crate::params::{ParamKind, ParamSpec};
+use crate::runner::{Metadata, Runner};
 
-use cgmath::{Matrix4, vec3, Deg, perspective, Point3};
+use cgmath::{Matrix4, vec3, Deg, perspective, Point3, Vector3};
 use cgmath::prelude::*;
-use gl::types::{GLfloat, GLsizei, GLsizeiptr};
-use crate::common::process_input;
+use crate::common::{load_image, process_event, process_input};
 use crate::shader;
+use crate::uniform::Sampler;
 
 pub struct Camera;
 
-const SCR_WIDTH: u32 = 800;
-const SCR_HEIGHT: u32 = 600;
+crate::register_runner!(Camera);
+
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        name: "cube_positions",
+        kind: ParamKind::Vec3List,
+        default: "0,0,0; 2,5,-15; -1.5,-2.2,-2.5; -3.8,-2,-12.3; 2.4,-0.4,-3.5; -1.7,3,-7.5; 1.3,-2,-2.5; 1.5,2,-2.5; 1.5,0.2,-1.5; -1.3,1,-1.5",
+        description: "World space position of each cube",
+    },
+    ParamSpec { name: "cube_count", kind: ParamKind::Int, default: "10", description: "How many of the cube positions to draw" },
+];
 
 impl Runner for Camera {
     fn chapter(&self) -> i32 { 1 }
@@ -21,49 +32,76 @@ impl Runner for Camera {
         "camera"
     }
 
-    fn run(&self) {
-        let mut camera = crate::camera::Camera::new(Point3::new(0.0, 0.0, 3.0));
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            description: "Flies a free look camera around textured cubes with WASD and the mouse",
+            url: Some("https://learnopengl.com/Getting-started/Camera"),
+            tags: &["camera", "input", "transformations", "textures"],
+            assets: &["shaders/1.8.camera.vert", "shaders/1.8.camera.frag", "textures/container.jpg", "textures/awesomeface.png"],
+            ..Metadata::default()
+        }
+    }
+
+    fn params(&self) -> &'static [ParamSpec] {
+        PARAMS
+    }
+
+    fn app(&self) -> Box<dyn App> {
+        Box::new(CameraApp {
+            camera: crate::camera::Camera::new(Point3::new(0.0, 0.0, 3.0)),
+            first_mouse: true,
+            last_x: 0.0,
+            last_y: 0.0,
+            shader_program: None,
+            vbo: 0,
+            vao: 0,
+            texture1: 0,
+            texture2: 0,
+            cube_positions: Vec::new(),
+        })
+    }
+}
 
-        let mut first_mouse = true;
-        let mut last_x = SCR_WIDTH as f32 / 2.0;
-        let mut last_y = SCR_HEIGHT as f32 / 2.0;
+struct CameraApp {
+    camera: crate::camera::Camera,
 
-        // timing
-        let mut delta_time: f32;
-        let mut last_frame_time: f32 = 0.0;
+    // mouse tracking
+    first_mouse: bool,
+    last_x: f32,
+    last_y: f32,
 
-        // Setup window initialization and configuration
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        #[cfg(target_os = "macos")]
-        glfw.window_hint(glfw::WindowHint::openGlForwardCompat(true));
+    shader_program: Option<shader::Shader>,
+    vbo: GLuint,
+    vao: GLuint,
+    texture1: GLuint,
+    texture2: GLuint,
+    cube_positions: Vec<Vector3<f32>>,
+}
 
-        // glfw window creation
-        let (mut window, events) = glfw.create_window(SCR_WIDTH, SCR_HEIGHT, "Camera Controls", glfw::WindowMode::Windowed)
-            .expect("Failed to create GLFW window");
+impl App for CameraApp {
+    fn setup(&mut self, ctx: &mut Context) -> Result<()> {
+        // get world space positions of cubes
+        let params = ctx.params(PARAMS)?;
+        let cube_count = params.int("cube_count").max(0) as usize;
+        self.cube_positions = params.vec3_list("cube_positions").iter().copied().take(cube_count).collect();
 
-        window.make_current();
-        window.set_framebuffer_size_polling(true);
-        window.set_cursor_pos_polling(true);
-        window.set_scroll_polling(true);
+        self.camera.apply_settings(ctx.camera_settings());
 
         // tell GLFW to capture our mouse
-        window.set_cursor_mode(glfw::CursorMode::Disabled);
-
-        // gl: load all OpenGL function pointers
-        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+        ctx.capture_cursor(true);
+        let (width, height) = ctx.framebuffer_size();
+        self.last_x = width as f32 / 2.0;
+        self.last_y = height as f32 / 2.0;
 
-        #[allow(non_snake_case)]
-        let (shader_program, VBO, VAO, texture1, texture2, cubePositions) = unsafe {
+        unsafe {
             // configure the global opengl state
             gl::Enable(gl::DEPTH_TEST);
 
             // build and compile our shader program
             let shader_program = shader::Shader::new(
-                std::path::Path::new("shaders/1.8.camera.vert"),
-                std::path::Path::new("shaders/1.8.camera.frag"),
-            );
+                &ctx.asset_path("shaders/1.8.camera.vert"),
+                &ctx.asset_path("shaders/1.8.camera.frag"),
+            )?;
 
             // set up vertex data and buffer(s) for cube
             let vertices = [
@@ -105,29 +143,12 @@ impl Runner for Camera {
                 -0.5, 0.5, -0.5, 0.0, 1.0
             ];
 
-            // get world space positions of cubes
-            let cube_positions = [
-                vec3(0.0f32, 0.0, 0.0),
-                vec3(2.0, 5.0, -15.0),
-                vec3(-1.5, -2.2, -2.5),
-                vec3(-3.8, -2.0, -12.3),
-                vec3(2.4, -0.4, -3.5),
-                vec3(-1.7, 3.0, -7.5),
-                vec3(1.3, -2.0, -2.5),
-                vec3(1.5, 2.0, -2.5),
-                vec3(1.5, 0.2, -1.5),
-                vec3(-1.3, 1.0, -1.5),
-            ];
-
-            #[allow(non_snake_case)]
-            let (mut VBO, mut VAO) = (0, 0);
-
-            gl::GenVertexArrays(1, &mut VAO);
-            gl::GenBuffers(1, &mut VBO);
+            gl::GenVertexArrays(1, &mut self.vao);
+            gl::GenBuffers(1, &mut self.vbo);
 
-            gl::BindVertexArray(VAO);
+            gl::BindVertexArray(self.vao);
 
-            gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
             gl::BufferData(gl::ARRAY_BUFFER,
                            (vertices.len() * std::mem::size_of::<GLfloat>()) as GLsizeiptr,
                            &vertices[0] as *const f32 as *const c_void,
@@ -143,11 +164,9 @@ impl Runner for Camera {
             gl::EnableVertexAttribArray(1);
 
             // load and create texture
-            let (mut texture1, mut texture2) = (0, 0);
-
             // texture 1
-            gl::GenTextures(1, &mut texture1);
-            gl::BindTexture(gl::TEXTURE_2D, texture1);
+            gl::GenTextures(1, &mut self.texture1);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture1);
             // set the texture wrapping parameters
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
@@ -155,7 +174,7 @@ impl Runner for Camera {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
             // load image, create texture and generate mipmaps
-            let img = image::open(std::path::Path::new("textures/container.jpg")).expect("Failed to load texture.");
+            let img = load_image(&ctx.asset_path("textures/container.jpg"))?;
             let data = img.as_bytes();
             gl::TexImage2D(gl::TEXTURE_2D,
                            0,
@@ -168,8 +187,8 @@ impl Runner for Camera {
                            &data[0] as *const u8 as *const c_void);
             gl::GenerateMipmap(gl::TEXTURE_2D);
             // texture 2
-            gl::GenTextures(1, &mut texture2);
-            gl::BindTexture(gl::TEXTURE_2D, texture2);
+            gl::GenTextures(1, &mut self.texture2);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture2);
             // set the texture wrapping parameters
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
@@ -177,7 +196,7 @@ impl Runner for Camera {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
             // load image create texture and generate mipmaps
-            let img = image::open(std::path::Path::new("textures/awesomeface.png")).expect("Failed to load texture");
+            let img = load_image(&ctx.asset_path("textures/awesomeface.png"))?;
             let img = img.flipv();
             let data = img.as_bytes();
             // note that the awesomeface.png has transparency and thus and alpha channel, so make sure to tell OpenGL the data type is of GL_RGBA
@@ -194,66 +213,71 @@ impl Runner for Camera {
 
             // tell opegl for each sampler to which texture unit it belongs to
             shader_program.use_program();
-            shader_program.set_int(&std::ffi::CString::new("texture1").unwrap(), 0);
-            shader_program.set_int(&std::ffi::CString::new("texture2").unwrap(), 1);
-
-            (shader_program, VBO, VAO, texture1, texture2, cube_positions)
-        };
-
-        while !window.should_close() {
-            let current_frame_time = glfw.get_time() as f32;
-            delta_time = current_frame_time - last_frame_time;
-            last_frame_time = current_frame_time;
-
-            // event handle
-            crate::common::process_events(&events, &mut first_mouse, &mut last_x, &mut last_y, &mut camera);
-
-            //input
-            process_input(&mut window, delta_time, &mut camera);
-
-            unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-
-                // bind textures on corresponding texture units
-                gl::ActiveTexture(gl::TEXTURE0);
-                gl::BindTexture(gl::TEXTURE_2D, texture1);
-                gl::ActiveTexture(gl::TEXTURE1);
-                gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-                // activate shader
-                shader_program.use_program();
-
-                // pass projection matrix to shader (note that in thiis case it cound change every frame)
-                let projection: Matrix4<f32> = perspective(Deg(camera.get_zoom()), SCR_WIDTH as f32 / SCR_HEIGHT as f32, 0.1, 100.0);
-                shader_program.set_mat4(&std::ffi::CString::new("projection").unwrap(), &projection);
-
-                // camera view transformation
-                let view = camera.get_view_matrix();
-                shader_program.set_mat4(&std::ffi::CString::new("view").unwrap(), &view);
-
-                // render boxes
-                gl::BindVertexArray(VAO);
-                for (i, position) in cubePositions.iter().enumerate() {
-                    let mut model: Matrix4<f32> = Matrix4::from_translation(*position);
-                    let angle = 20.0 * i as f32;
-                    model = model * Matrix4::from_axis_angle(vec3(1.0, 0.3, 0.5).normalize(), Deg(angle));
-                    shader_program.set_mat4(&std::ffi::CString::new("model").unwrap(), &model);
-
-                    gl::DrawArrays(gl::TRIANGLES, 0, 36);
-                }
-            }
+            shader_program.set("texture1", Sampler(0));
+            shader_program.set("texture2", Sampler(1));
+
+            self.shader_program = Some(shader_program);
+        }
 
-            window.swap_buffers();
-            glfw.poll_events();
+        Ok(())
+    }
+
+    fn on_event(&mut self, _ctx: &mut Context, event: &glfw::WindowEvent) {
+        process_event(event, &mut self.first_mouse, &mut self.last_x, &mut self.last_y, &mut self.camera);
+    }
+
+    fn update(&mut self, ctx: &mut Context, delta_time: f32) {
+        //input
+        process_input(ctx, delta_time, &mut self.camera);
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        let shader_program = self.shader_program.as_ref().unwrap();
+
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            // bind textures on corresponding texture units
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture1);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture2);
+
+            // activate shader
+            shader_program.use_program();
+
+            // pass projection matrix to shader (note that in thiis case it cound change every frame)
+            let projection: Matrix4<f32> = perspective(Deg(self.camera.get_zoom()), ctx.aspect_ratio(), 0.1, 100.0);
+            shader_program.set("projection", projection);
+
+            // camera view transformation
+            let view = self.camera.get_view_matrix();
+            shader_program.set("view", view);
+
+            // render boxes
+            gl::BindVertexArray(self.vao);
+            for (i, position) in self.cube_positions.iter().enumerate() {
+                let mut model: Matrix4<f32> = Matrix4::from_translation(*position);
+                let angle = 20.0 * i as f32;
+                model = model * Matrix4::from_axis_angle(vec3(1.0, 0.3, 0.5).normalize(), Deg(angle));
+                shader_program.set("model", model);
+
+                gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            }
         }
+    }
+
+    fn teardown(&mut self, ctx: &mut Context) {
+        ctx.capture_cursor(false);
 
         unsafe {
-            gl::DeleteVertexArrays(1, &VAO);
-            gl::DeleteBuffers(1, &VBO);
+            gl::Disable(gl::DEPTH_TEST);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteTextures(1, &self.texture1);
+            gl::DeleteTextures(1, &self.texture2);
         }
+        self.shader_program = None;
     }
 }
-
-#[allow(dead_code)]
-fn process_events(_window: &mut glfw::Window, _events: &Receiver<(f64, glfw::WindowEvent)>) {}
\ No newline at end of file
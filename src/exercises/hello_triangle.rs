@@ -1,14 +1,14 @@
-use std::ffi::{c_void, CString};
+use std::ffi::c_void;
 use std::ptr;
-use std::sync::mpsc::Receiver;
-use gl::types::{GLfloat, GLint, GLsizeiptr, GLuint};
-use glfw::{Action, Context, Key};
-use crate::runner::Runner;
+use gl::types::{GLfloat, GLsizeiptr, GLuint};
+use crate::app::{App, Context};
+use crate::error::Result;
+use crate::runner::{Metadata, Runner};
+use crate::shader::Shader;
 
 pub struct HelloTriangle;
 
-const SCR_WIDTH: u32 = 800;
-const SCR_HEIGHT: u32 = 600;
+crate::register_runner!(HelloTriangle);
 
 const VERTICES: [f32; 12] = [
     0.5, 0.5, 0.0, // top right
@@ -46,97 +46,71 @@ impl Runner for HelloTriangle {
         "hello triangle"
     }
 
-    fn run(&self) {
-        // glfw: initalize and configure
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        #[cfg(target_os = "macos")]
-        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-        // glfw window creation
-        let (mut window, events) =
-            glfw.create_window(SCR_WIDTH, SCR_HEIGHT, self.name(), glfw::WindowMode::Windowed)
-                .expect("Failed to create GLFW window");
-
-        window.make_current();
-        window.set_key_polling(true);
-        window.set_framebuffer_size_polling(true);
-
-        // gl: load all OpenGL function pointers
-        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-        // Build and compile the shader program
-        #[allow(non_snake_case)]
-        let (shader_program,  VAO) = unsafe {
-
-            // vertex shader
-
-            let vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
-            let c_str_vert = CString::new(VERTEX_SHADER_SOURCE.as_bytes()).unwrap();
-            gl::ShaderSource(vertex_shader, 1, &c_str_vert.as_ptr(), ptr::null());
-            gl::CompileShader(vertex_shader);
-
-            // check for shader compile errors
-            let mut success = gl::FALSE as GLint;
-            let mut info_log = Vec::with_capacity(512);
-            info_log.resize(512 - 1, 0);
-            gl::GetShaderiv(vertex_shader, gl::COMPILE_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetShaderInfoLog(vertex_shader, 512, ptr::null_mut(), info_log.as_mut_ptr() as *mut gl::types::GLchar);
-                println!("ERROR::SHADER::VERTEX::COMPILATION_FAILED\n{}", std::str::from_utf8(&info_log).unwrap());
-            }
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            description: "Draws a rectangle from two triangles with an element buffer",
+            url: Some("https://learnopengl.com/Getting-started/Hello-Triangle"),
+            tags: &["vertex buffers", "shaders"],
+            assets: &[],
+            ..Metadata::default()
+        }
+    }
 
-            // fragment shader
+    fn app(&self) -> Box<dyn App> {
+        Box::<HelloTriangleApp>::default()
+    }
 
-            let fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-            let c_str_frag = CString::new(FRAGMENT_SHADER_SOURCE.as_bytes()).unwrap();
-            gl::ShaderSource(fragment_shader, 1, &c_str_frag.as_ptr(), ptr::null());
-            gl::CompileShader(fragment_shader);
+    fn variants(&self) -> &'static [&'static str] {
+        &[
+            "two triangles next to each other with glDrawArrays",
+            "two triangles from separate VAOs and VBOs",
+            "two triangles drawn with different shader programs",
+        ]
+    }
 
-            // check for shader compile errors
-            gl::GetShaderiv(fragment_shader, gl::COMPILE_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetShaderInfoLog(fragment_shader, 512, ptr::null_mut(), info_log.as_mut_ptr() as *mut gl::types::GLchar);
-                println!("ERROR::SHADER::FRAGMENT::COMPILATION_FAILED\n{}", std::str::from_utf8(&info_log).unwrap());
-            }
+    fn variant_app(&self, variant: u32) -> Box<dyn App> {
+        match variant {
+            1 => Box::<AdjacentTrianglesApp>::default(),
+            2 => Box::<SeparateBuffersApp>::default(),
+            3 => Box::<TwoProgramsApp>::default(),
+            _ => unreachable!("hello triangle has no exercise {variant}"),
+        }
+    }
+}
 
-            // link shaders
-
-            let shader_program = gl::CreateProgram();
-            gl::AttachShader(shader_program, vertex_shader);
-            gl::AttachShader(shader_program, fragment_shader);
-            gl::LinkProgram(shader_program);
-            // check for linking errors
-            gl::GetProgramiv(shader_program, gl::LINK_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetProgramInfoLog(shader_program, 512, ptr::null_mut(), info_log.as_mut_ptr() as *mut gl::types::GLchar);
-                println!("ERROR::SHADER::PROGRAM::COMPILATION_FAILED\n{}", std::str::from_utf8(&info_log).unwrap());
-            }
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
+#[derive(Default)]
+struct HelloTriangleApp {
+    shader_program: Option<Shader>,
+    vao: GLuint,
+    vbo: GLuint,
+    ebo: GLuint,
+}
 
+impl App for HelloTriangleApp {
+    fn setup(&mut self, _ctx: &mut Context) -> Result<()> {
+        // build and compile the shader program
+        self.shader_program = Some(Shader::from_source(VERTEX_SHADER_SOURCE, FRAGMENT_SHADER_SOURCE)?);
+
+        unsafe {
             // gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 3 * std::mem::size_of::<GLfloat>() as gl::types::GLsizei, ptr::null());
             // gl::EnableVertexAttribArray(0);
 
             // feed in the data
 
-            #[allow(non_snake_case)]
-            let (mut VBO, mut VAO, mut EBO) = (0, 0, 0);
-            gl::GenVertexArrays(1, &mut VAO);
-            gl::GenBuffers(1, &mut VBO);
-            gl::GenBuffers(1, &mut EBO);
+            gl::GenVertexArrays(1, &mut self.vao);
+            gl::GenBuffers(1, &mut self.vbo);
+            gl::GenBuffers(1, &mut self.ebo);
 
             // bind the Vertex Array Object first, then bind and set vertex buffer(s), and then configure vertex attributes(s).
-            gl::BindVertexArray(VAO);
+            gl::BindVertexArray(self.vao);
 
-            gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
             gl::BufferData(gl::ARRAY_BUFFER,
                            (VERTICES.len() * std::mem::size_of::<GLfloat>()) as gl::types::GLsizeiptr,
                            &VERTICES[0] as *const f32 as *const c_void,
                            gl::STATIC_DRAW);
 
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, EBO);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
             gl::BufferData(gl::ELEMENT_ARRAY_BUFFER,
                            (INDEXES.len() * std::mem::size_of::<GLuint>()) as GLsizeiptr,
                            &INDEXES[0] as *const u32 as *const c_void,
@@ -154,45 +128,201 @@ impl Runner for HelloTriangle {
 
             // uncomment this call to draw in wireframe polygons.
             // gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+        }
 
-            (shader_program, VAO)
-        };
+        Ok(())
+    }
 
-        // uncomment this call to draw in wireframe polygons.
-        // gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+    fn render(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
 
-        // render loop
-        while !window.should_close() {
-            // events
-            process_events(&mut window, &events);
+            if let Some(shader_program) = &self.shader_program {
+                shader_program.use_program();
+            }
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteBuffers(1, &self.ebo);
+        }
+        self.shader_program = None;
+    }
+}
+
+/// Two triangles next to each other, one after the other in a single vertex buffer
+const ADJACENT_TRIANGLES: [f32; 18] = [
+    // first triangle
+    -0.9, -0.5, 0.0, // left
+    -0.0, -0.5, 0.0, // right
+    -0.45, 0.5, 0.0, // top
+    // second triangle
+    0.0, -0.5, 0.0, // left
+    0.9, -0.5, 0.0, // right
+    0.45, 0.5, 0.0, // top
+];
+
+const YELLOW_FRAGMENT_SHADER_SOURCE: &str = r#"
+    #version 330 core
+    out vec4 FragColor;
+    void main() {
+       FragColor = vec4(1.0f, 1.0f, 0.0f, 1.0f);
+    }
+"#;
+
+/// Creates a VAO and VBO holding `vertices` as tightly packed positions
+///
+/// # Arguments
+///
+/// * `vertices` x, y, z for every vertex
+unsafe fn upload_positions(vertices: &[f32]) -> (GLuint, GLuint) {
+    let (mut vao, mut vbo) = (0, 0);
+    gl::GenVertexArrays(1, &mut vao);
+    gl::GenBuffers(1, &mut vbo);
+
+    gl::BindVertexArray(vao);
+    gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+    gl::BufferData(gl::ARRAY_BUFFER,
+                   std::mem::size_of_val(vertices) as GLsizeiptr,
+                   vertices.as_ptr() as *const c_void,
+                   gl::STATIC_DRAW);
+
+    gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 3 * std::mem::size_of::<GLfloat>() as gl::types::GLsizei, ptr::null());
+    gl::EnableVertexAttribArray(0);
+
+    gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+    gl::BindVertexArray(0);
+
+    (vao, vbo)
+}
+
+/// Exercise 1: draw two triangles next to each other using glDrawArrays by adding more vertices to the data
+#[derive(Default)]
+struct AdjacentTrianglesApp {
+    shader_program: Option<Shader>,
+    vao: GLuint,
+    vbo: GLuint,
+}
 
-            // Render
-            unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
+impl App for AdjacentTrianglesApp {
+    fn setup(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.shader_program = Some(Shader::from_source(VERTEX_SHADER_SOURCE, FRAGMENT_SHADER_SOURCE)?);
+        (self.vao, self.vbo) = unsafe { upload_positions(&ADJACENT_TRIANGLES) };
 
-                gl::UseProgram(shader_program);
-                gl::BindVertexArray(VAO);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
+        Ok(())
+    }
+
+    fn render(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            if let Some(shader_program) = &self.shader_program {
+                shader_program.use_program();
+            }
+            gl::BindVertexArray(self.vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6);
+        }
+    }
+
+    fn teardown(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+        }
+        self.shader_program = None;
+    }
+}
+
+/// Exercise 2: create the same two triangles using two different VAOs and VBOs for their data
+#[derive(Default)]
+struct SeparateBuffersApp {
+    shader_program: Option<Shader>,
+    vaos: [GLuint; 2],
+    vbos: [GLuint; 2],
+}
+
+impl App for SeparateBuffersApp {
+    fn setup(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.shader_program = Some(Shader::from_source(VERTEX_SHADER_SOURCE, FRAGMENT_SHADER_SOURCE)?);
+        unsafe {
+            (self.vaos[0], self.vbos[0]) = upload_positions(&ADJACENT_TRIANGLES[..9]);
+            (self.vaos[1], self.vbos[1]) = upload_positions(&ADJACENT_TRIANGLES[9..]);
+        }
+
+        Ok(())
+    }
+
+    fn render(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            if let Some(shader_program) = &self.shader_program {
+                shader_program.use_program();
             }
+            for vao in self.vaos {
+                gl::BindVertexArray(vao);
+                gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            }
+        }
+    }
 
-            // glfw: swap buffers and poll IO events (keys presssed/released, mouse moved, etc)
-            window.swap_buffers();
-            glfw.poll_events();
+    fn teardown(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::DeleteVertexArrays(2, self.vaos.as_ptr());
+            gl::DeleteBuffers(2, self.vbos.as_ptr());
         }
+        self.shader_program = None;
     }
 }
 
-fn process_events(window: &mut glfw::Window, events: &Receiver<(f64, glfw::WindowEvent)>) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            glfw::WindowEvent::FramebufferSize(width, height) => {
-                // make sure the viewport matches the new window dimesnions.
-                // note that the width and height will be significantly larger than specified on retina displays
-                unsafe { gl::Viewport(0, 0, width, height) }
+/// Exercise 3: two shader programs where the second uses a different fragment shader that outputs yellow
+#[derive(Default)]
+struct TwoProgramsApp {
+    shader_programs: Vec<Shader>,
+    vaos: [GLuint; 2],
+    vbos: [GLuint; 2],
+}
+
+impl App for TwoProgramsApp {
+    fn setup(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.shader_programs = vec![
+            Shader::from_source(VERTEX_SHADER_SOURCE, FRAGMENT_SHADER_SOURCE)?,
+            Shader::from_source(VERTEX_SHADER_SOURCE, YELLOW_FRAGMENT_SHADER_SOURCE)?,
+        ];
+        unsafe {
+            (self.vaos[0], self.vbos[0]) = upload_positions(&ADJACENT_TRIANGLES[..9]);
+            (self.vaos[1], self.vbos[1]) = upload_positions(&ADJACENT_TRIANGLES[9..]);
+        }
+
+        Ok(())
+    }
+
+    fn render(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            for (shader_program, vao) in self.shader_programs.iter().zip(self.vaos) {
+                shader_program.use_program();
+                gl::BindVertexArray(vao);
+                gl::DrawArrays(gl::TRIANGLES, 0, 3);
             }
-            glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
-            _ => {}
         }
     }
-}
\ No newline at end of file
+
+    fn teardown(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::DeleteVertexArrays(2, self.vaos.as_ptr());
+            gl::DeleteBuffers(2, self.vbos.as_ptr());
+        }
+        self.shader_programs.clear();
+    }
+}
@@ -1,11 +1,10 @@
-use std::sync::mpsc::Receiver;
-use glfw::{Action, Context, Key};
-use crate::runner::Runner;
+use crate::app::{App, Context};
+use crate::error::Result;
+use crate::runner::{Metadata, Runner};
 
 pub struct HelloWindow;
 
-const SCR_WIDTH: u32 = 800;
-const SCR_HEIGHT: u32 = 600;
+crate::register_runner!(HelloWindow);
 
 impl Runner for HelloWindow {
     fn chapter(&self) -> i32 { 1 }
@@ -14,54 +13,34 @@ impl Runner for HelloWindow {
         "hello window"
     }
 
-    fn run(&self) {
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        #[cfg(target_os = "macos")]
-        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-        let (mut window, events) = glfw.create_window(SCR_WIDTH, SCR_HEIGHT, "LearnOpenGL", glfw::WindowMode::Windowed)
-            .expect("Failed to create GLFW window");
-
-        window.make_current();
-        window.set_key_polling(true);
-        window.set_framebuffer_size_polling(true);
-
-        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-        // render loop
-        while !window.should_close() {
-            // events
-            process_events(&mut window, &events);
-
-           // rendering commands
-            unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-            }
-
-            // glfw: swap buffers and poll IO events
-            window.swap_buffers();
-            glfw.poll_events();
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            description: "Clears the window to a solid color every frame",
+            url: Some("https://learnopengl.com/Getting-started/Hello-Window"),
+            tags: &["window", "input"],
+            assets: &[],
+            ..Metadata::default()
         }
+    }
 
+    fn app(&self) -> Box<dyn App> {
+        Box::new(HelloWindowApp)
+    }
+}
 
+/// Clears the window to a solid color every frame
+struct HelloWindowApp;
 
-
+impl App for HelloWindowApp {
+    fn setup(&mut self, _ctx: &mut Context) -> Result<()> {
+        Ok(())
     }
-}
 
-fn process_events(window: &mut glfw::Window, events: &Receiver<(f64, glfw::WindowEvent)>) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            glfw::WindowEvent::FramebufferSize(width, height) => {
-                // make sure the viewport matches the new window dimesnions.
-                // note that the width and height will be significantly larger than specified on retina displays
-                unsafe { gl::Viewport(0, 0, width, height) }
-            }
-            glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
-            _ => {}
+    fn render(&mut self, _ctx: &mut Context) {
+        // rendering commands
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
         }
     }
-}
\ No newline at end of file
+}
@@ -1,15 +1,14 @@
 use std::ffi::c_void;
 use std::ptr;
-use std::sync::mpsc::Receiver;
 use gl::types::{GLfloat, GLsizeiptr, GLuint};
-use glfw::{Action, Context, Key};
-use crate::runner::Runner;
+use crate::app::{App, Context};
+use crate::error::Result;
+use crate::runner::{Metadata, Runner};
 use crate::shader;
 
 pub struct Shader;
 
-const SCR_WIDTH: u32 = 800;
-const SCR_HEIGHT: u32 = 600;
+crate::register_runner!(Shader);
 
 const VERTICES: [f32; 24] = [
     // positions   // colors
@@ -101,54 +100,54 @@ impl Runner for Shader {
         "shader"
     }
 
-    fn run(&self) {
-        // glfw: initalize and configure
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        #[cfg(target_os = "macos")]
-        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-        // glfw window creation
-        let (mut window, events) =
-            glfw.create_window(SCR_WIDTH, SCR_HEIGHT, self.name(), glfw::WindowMode::Windowed)
-                .expect("Failed to create GLFW window");
-
-        window.make_current();
-        window.set_key_polling(true);
-        window.set_framebuffer_size_polling(true);
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            description: "Passes per vertex colors from the vertex to the fragment shader",
+            url: Some("https://learnopengl.com/Getting-started/Shaders"),
+            tags: &["shaders", "uniforms"],
+            assets: &["shaders/firstShader.vert", "shaders/firstShader.frag"],
+            ..Metadata::default()
+        }
+    }
 
-        // gl: load all OpenGL function pointers
-        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+    fn app(&self) -> Box<dyn App> {
+        Box::<ShaderApp>::default()
+    }
+}
 
-        let vertex_path = std::path::Path::new("shaders/firstShader.vert");
-        let fragment_path = std::path::Path::new("shaders/firstShader.frag");
+#[derive(Default)]
+struct ShaderApp {
+    shader_program: Option<shader::Shader>,
+    vao: GLuint,
+    vbo: GLuint,
+    ebo: GLuint,
+}
 
+impl App for ShaderApp {
+    fn setup(&mut self, ctx: &mut Context) -> Result<()> {
+        let vertex_path = ctx.asset_path("shaders/firstShader.vert");
+        let fragment_path = ctx.asset_path("shaders/firstShader.frag");
 
         // Build and compile the shader program
-        #[allow(non_snake_case)]
-        let (shader_program, VAO) = unsafe {
-
-            let shader_program = shader::Shader::new(vertex_path, fragment_path);
+        self.shader_program = Some(shader::Shader::new(&vertex_path, &fragment_path)?);
 
+        unsafe {
             // feed in the data
 
-            #[allow(non_snake_case)]
-            let (mut VBO, mut VAO, mut EBO) = (0, 0, 0);
-            gl::GenVertexArrays(1, &mut VAO);
-            gl::GenBuffers(1, &mut VBO);
-            gl::GenBuffers(1, &mut EBO);
+            gl::GenVertexArrays(1, &mut self.vao);
+            gl::GenBuffers(1, &mut self.vbo);
+            gl::GenBuffers(1, &mut self.ebo);
 
             // bind the Vertex Array Object first, then bind and set vertex buffer(s), and then configure vertex attributes(s).
-            gl::BindVertexArray(VAO);
+            gl::BindVertexArray(self.vao);
 
-            gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
             gl::BufferData(gl::ARRAY_BUFFER,
                            (VERTICES.len() * std::mem::size_of::<GLfloat>()) as gl::types::GLsizeiptr,
                            &VERTICES[0] as *const f32 as *const c_void,
                            gl::STATIC_DRAW);
 
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, EBO);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
             gl::BufferData(gl::ELEMENT_ARRAY_BUFFER,
                            (INDEXES.len() * std::mem::size_of::<GLuint>()) as GLsizeiptr,
                            &INDEXES[0] as *const u32 as *const c_void,
@@ -170,49 +169,33 @@ impl Runner for Shader {
 
             // uncomment this call to draw in wireframe polygons.
             // gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+        }
 
-            (shader_program, VAO)
-        };
-
-        // uncomment this call to draw in wireframe polygons.
-        // gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
-
-        // render loop
-        while !window.should_close() {
-            // events
-            process_events(&mut window, &events);
-
-            // Render
-            unsafe {
-                // clear the colorbuffer
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
+        Ok(())
+    }
 
-                // activate program
-                shader_program.use_program();
+    fn render(&mut self, _ctx: &mut Context) {
+        let shader_program = self.shader_program.as_ref().unwrap();
 
+        unsafe {
+            // clear the colorbuffer
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
 
-                gl::BindVertexArray(VAO);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
-            }
+            // activate program
+            shader_program.use_program();
 
-            // glfw: swap buffers and poll IO events (keys presssed/released, mouse moved, etc)
-            window.swap_buffers();
-            glfw.poll_events();
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, ptr::null());
         }
     }
-}
 
-fn process_events(window: &mut glfw::Window, events: &Receiver<(f64, glfw::WindowEvent)>) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            glfw::WindowEvent::FramebufferSize(width, height) => {
-                // make sure the viewport matches the new window dimesnions.
-                // note that the width and height will be significantly larger than specified on retina displays
-                unsafe { gl::Viewport(0, 0, width, height) }
-            }
-            glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
-            _ => {}
+    fn teardown(&mut self, _ctx: &mut Context) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteBuffers(1, &self.ebo);
         }
+        self.shader_program = None;
     }
-}
\ No newline at end of file
+}
@@ -1,13 +1,23 @@
 use std::ffi::c_void;
-use std::sync::mpsc::Receiver;
-use gl::types::{GLfloat, GLsizeiptr};
-use glfw::{Action, Context, Key};
-use crate::runner::Runner;
+use gl::types::{GLfloat, GLsizeiptr, GLuint};
+use crate::app::{App, Context};
+use crate::common::load_image;
+use crate::error::{Error, Result};
+use crate::params::{ParamKind, ParamSpec};
+use crate::runner::{Metadata, Runner};
+use crate::shader;
+use crate::uniform::Sampler;
 
 pub struct Textures;
 
-const SCR_WIDTH: u32 = 800;
-const SCR_HEIGHT: u32 = 600;
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec { name: "texture1", kind: ParamKind::Text, default: "textures/container.jpg", description: "Image shown as the base texture" },
+    ParamSpec { name: "texture2", kind: ParamKind::Text, default: "textures/awesomeface.png", description: "Image mixed over the base texture" },
+    ParamSpec { name: "ratio", kind: ParamKind::Float, default: "0.2", description: "How much of texture2 to mix in, from 0 to 1" },
+    ParamSpec { name: "animate_ratio", kind: ParamKind::Bool, default: "true", description: "Fade texture2 back and forth over time instead of using ratio" },
+];
+
+crate::register_runner!(Textures);
 
 impl Runner for Textures {
     fn chapter(&self) -> i32 { 1 }
@@ -16,34 +26,51 @@ impl Runner for Textures {
         "textures"
     }
 
-    fn run(&self) {
-        // initialize glfw
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        glfw.window_hint(glfw::WindowHint::ContextVersion(3, 3));
-        glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        #[cfg(target_os = "macos")]
-        glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-
-        // glfw window creation
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            description: "Mixes two textures on a quad, fading between them over time",
+            url: Some("https://learnopengl.com/Getting-started/Textures"),
+            tags: &["textures", "shaders"],
+            assets: &["shaders/1.4.texture.vert", "shaders/1.4.texture.frag", "textures/container.jpg", "textures/awesomeface.png"],
+            ..Metadata::default()
+        }
+    }
 
-        let (mut window, events) =
-            glfw.create_window(SCR_WIDTH, SCR_HEIGHT, "LearnOpenGL: texture", glfw::WindowMode::Windowed)
-                .expect("Failed to created glfw window");
+    fn params(&self) -> &'static [ParamSpec] {
+        PARAMS
+    }
 
-        window.make_current();
-        window.set_key_polling(true);
-        window.set_framebuffer_size_polling(true);
+    fn app(&self) -> Box<dyn App> {
+        Box::<TexturesApp>::default()
+    }
+}
 
+#[derive(Default)]
+struct TexturesApp {
+    shader_program: Option<shader::Shader>,
+    vbo: GLuint,
+    vao: GLuint,
+    ebo: GLuint,
+    texture1: GLuint,
+    texture2: GLuint,
+    /// Fixed mix ratio, `None` to animate it
+    ratio: Option<f32>,
+}
 
-        // gl: load all OpenGL function pointers
-        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+impl App for TexturesApp {
+    fn setup(&mut self, ctx: &mut Context) -> Result<()> {
+        let params = ctx.params(PARAMS)?;
+        let ratio = params.float("ratio");
+        if !(0.0..=1.0).contains(&ratio) {
+            return Err(Error::InvalidParam { name: "ratio".to_string(), reason: format!("{ratio} is not between 0 and 1") });
+        }
+        self.ratio = if params.bool("animate_ratio") { None } else { Some(ratio) };
 
-        #[allow(non_snake_case)]
-        let (shaderProgram, VBO, VAO, EBO, texture1, texture2) = unsafe {
+        unsafe {
             // build and compile the shader program.
-            let vert_path = std::path::Path::new("shaders/1.4.texture.vert");
-            let frag_path = std::path::Path::new("shaders/1.4.texture.frag");
-            let shaderProgram = crate::shader::Shader::new(vert_path, frag_path);
+            let vert_path = ctx.asset_path("shaders/1.4.texture.vert");
+            let frag_path = ctx.asset_path("shaders/1.4.texture.frag");
+            let shader_program = crate::shader::Shader::new(&vert_path, &frag_path)?;
 
             // set up vertex data and buffeers and configure vertex attributes
             let vertices: [f32; 32] = [
@@ -59,23 +86,19 @@ impl Runner for Textures {
                 1, 2, 3, // second triangle
             ];
 
-            let mut VBO = 0;
-            let mut VAO = 0;
-            let mut EBO = 0;
-
-            gl::GenVertexArrays(1, &mut VAO);
-            gl::GenBuffers(1, &mut VBO);
-            gl::GenBuffers(1, &mut EBO);
+            gl::GenVertexArrays(1, &mut self.vao);
+            gl::GenBuffers(1, &mut self.vbo);
+            gl::GenBuffers(1, &mut self.ebo);
 
-            gl::BindVertexArray(VAO);
+            gl::BindVertexArray(self.vao);
 
-            gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
             gl::BufferData(gl::ARRAY_BUFFER,
                            (vertices.len() * std::mem::size_of::<GLfloat>()) as GLsizeiptr,
                            &vertices as *const f32 as *const std::ffi::c_void,
                            gl::STATIC_DRAW);
 
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, EBO);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
             gl::BufferData(gl::ELEMENT_ARRAY_BUFFER,
                            (indices.len() * std::mem::size_of::<GLfloat>()) as GLsizeiptr,
                            &indices[0] as *const i32 as *const c_void,
@@ -94,9 +117,8 @@ impl Runner for Textures {
             gl::VertexAttribPointer(2, 2, gl::FLOAT, gl::FALSE, stride, (6 * std::mem::size_of::<GLfloat>()) as *const c_void);
             gl::EnableVertexAttribArray(2);
 
-            let mut texture_1 = 0;
-            gl::GenTextures(1, &mut texture_1);
-            gl::BindTexture(gl::TEXTURE_2D, texture_1); // all upcoming GL_TEXTURE_2D ops now affect this texture object
+            gl::GenTextures(1, &mut self.texture1);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture1); // all upcoming GL_TEXTURE_2D ops now affect this texture object
 
             // set the texture wrapping parameters
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
@@ -106,8 +128,9 @@ impl Runner for Textures {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
 
-            let img = image::open(std::path::Path::new("textures/container.jpg")).expect("failed to load texture");
-            let data = img.as_bytes();
+            // converted so images with any channel layout match the formats given below
+            let img = load_image(&ctx.asset_path(params.text("texture1")))?.to_rgb8();
+            let data = img.as_raw();
             gl::TexImage2D(gl::TEXTURE_2D,
                            0,
                            gl::RGB as i32,
@@ -119,9 +142,8 @@ impl Runner for Textures {
                            data.as_ptr() as *const c_void);
             gl::GenerateMipmap(gl::TEXTURE_2D);
 
-            let mut texture_2 = 0;
-            gl::GenTextures(1, &mut texture_2);
-            gl::BindTexture(gl::TEXTURE_2D, texture_2); // all upcoming GL_TEXTURE_2D ops now affect this texture object
+            gl::GenTextures(1, &mut self.texture2);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture2); // all upcoming GL_TEXTURE_2D ops now affect this texture object
 
             // set the texture wrapping parameters
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
@@ -131,9 +153,9 @@ impl Runner for Textures {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
 
-            let img = image::open(std::path::Path::new("textures/awesomeface.png")).expect("failed to load texture");
-            let img = img.flipv();
-            let data = img.as_bytes();
+            let img = load_image(&ctx.asset_path(params.text("texture2")))?;
+            let img = img.flipv().to_rgba8();
+            let data = img.as_raw();
             gl::TexImage2D(gl::TEXTURE_2D,
                            0,
                            gl::RGB as i32,
@@ -147,66 +169,52 @@ impl Runner for Textures {
 
             // tell opelgl for each sampler which texture unit it belongs to.
 
-            shaderProgram.use_program();
+            shader_program.use_program();
             // either set it manually like:
-            // gl::uniform1i(gl::GetUniformLocation(shaderProgram.get_id(), c_str!("texture1").as_ptr()), 0); // using c_str! to avoid runtime overhead
+            // gl::uniform1i(gl::GetUniformLocation(shader_program.get_id(), c_str!("texture1").as_ptr()), 0); // using c_str! to avoid runtime overhead
             // or set it ivia the texture class
-            shaderProgram.set_int(&std::ffi::CString::new("texture1").unwrap(), 0);
-            shaderProgram.set_int(&std::ffi::CString::new("texture2").unwrap(), 1);
+            shader_program.set("texture1", Sampler(0));
+            shader_program.set("texture2", Sampler(1));
 
-            shaderProgram.set_float(&std::ffi::CString::new("ratio").unwrap(), 0.0);
+            shader_program.set("ratio", 0.0f32);
 
+            self.shader_program = Some(shader_program);
+        }
 
+        Ok(())
+    }
 
-            (shaderProgram, VBO, VAO, EBO, texture_1, texture_2)
-        };
-
-        // render loop
-        while !window.should_close() {
-            process_events(&mut window, &events);
-
-            // render
-            unsafe {
-                gl::ClearColor(0.2, 0.3, 0.3, 1.0);
-                gl::Clear(gl::COLOR_BUFFER_BIT);
-
-                gl::ActiveTexture(gl::TEXTURE0);
-                gl::BindTexture(gl::TEXTURE_2D, texture1);
-                gl::ActiveTexture(gl::TEXTURE1);
-                gl::BindTexture(gl::TEXTURE_2D, texture2);
-
-                let time = glfw.get_time();
-                let ratio = (time.sin() + 1.0) as f32;
+    fn render(&mut self, ctx: &mut Context) {
+        let shader_program = self.shader_program.as_ref().unwrap();
 
+        unsafe {
+            gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
 
-                shaderProgram.set_float(&std::ffi::CString::new("ratio").unwrap(), ratio );
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture1);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture2);
 
-                shaderProgram.use_program();
+            // uniforms go to the program in use, so activate it first
+            shader_program.use_program();
 
-                gl::BindVertexArray(VAO);
-                gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-            }
+            let ratio = self.ratio.unwrap_or_else(|| (ctx.time().sin() + 1.0) as f32);
+            shader_program.set("ratio", ratio);
 
-            window.swap_buffers();
-            glfw.poll_events();
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
         }
+    }
 
+    fn teardown(&mut self, _ctx: &mut Context) {
         unsafe {
-            gl::DeleteVertexArrays(1, &VAO);
-            gl::DeleteBuffers(1, &VBO);
-            gl::DeleteBuffers(1, &EBO);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteBuffers(1, &self.ebo);
+            gl::DeleteTextures(1, &self.texture1);
+            gl::DeleteTextures(1, &self.texture2);
         }
+        self.shader_program = None;
     }
 }
-
-fn process_events(window: &mut glfw::Window, events: &Receiver<(f64, glfw::WindowEvent)>) {
-    for (_, event) in glfw::flush_messages(events) {
-        match event {
-            glfw::WindowEvent::FramebufferSize(width, height) => {
-                unsafe { gl::Viewport(0, 0, width, height) }
-            }
-            glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
-            _ => {}
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+use image::{Rgb, RgbImage};
+use crate::app::{self, Backend, Context, Settings, WindowSettings};
+use crate::error;
+use crate::headless::Headless;
+use crate::runner::Runner;
+
+/// Environment variable that makes `check` (re)write the reference images instead of comparing
+pub const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+/// Environment variable that makes `headless_or_skip` fail tests instead of skipping them
+pub const REQUIRE_HEADLESS_ENV: &str = "REQUIRE_HEADLESS";
+
+/// Frames rendered before an exercise is captured
+pub const FRAMES: u32 = 10;
+
+/// Seconds the clock advances per frame, so animations land on the same state every run
+pub const TIME_STEP: f64 = 1.0 / 60.0;
+
+/// How far a rendered image may stray from its reference
+#[derive(Clone, Copy, Debug)]
+pub struct Tolerance {
+    /// Largest allowed difference of any color channel for a pixel to count as matching
+    pub per_channel: u8,
+    /// How many pixels may exceed `per_channel` before the images are considered different
+    pub max_mismatched_pixels: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance { per_channel: 2, max_mismatched_pixels: 0 }
+    }
+}
+
+/// Result of comparing a rendered image with its reference
+pub struct Comparison {
+    /// The largest channel difference found in any pixel
+    pub max_difference: u8,
+    /// Number of pixels whose difference exceeds the tolerance
+    pub mismatched_pixels: usize,
+    /// The reference dimmed to grey with the mismatched pixels marked in red
+    pub diff: RgbImage,
+}
+
+impl Comparison {
+    /// Whether the images are close enough for `tolerance`
+    pub fn passed(&self, tolerance: &Tolerance) -> bool {
+        self.mismatched_pixels <= tolerance.max_mismatched_pixels
+    }
+}
+
+/// Compares two images pixel by pixel
+///
+/// # Arguments
+///
+/// * `actual` the freshly rendered image
+/// * `expected` the reference image
+/// * `tolerance` decides which pixels count as mismatched
+pub fn compare(actual: &RgbImage, expected: &RgbImage, tolerance: &Tolerance) -> Result<Comparison, String> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(format!("size {:?} does not match the reference size {:?}", actual.dimensions(), expected.dimensions()));
+    }
+
+    let mut max_difference = 0;
+    let mut mismatched_pixels = 0;
+    let mut diff = RgbImage::new(expected.width(), expected.height());
+
+    for ((a, e), d) in actual.pixels().zip(expected.pixels()).zip(diff.pixels_mut()) {
+        let difference = a.0.iter().zip(e.0.iter()).map(|(a, e)| a.abs_diff(*e)).max().unwrap_or(0);
+        max_difference = max_difference.max(difference);
+
+        *d = if difference > tolerance.per_channel {
+            mismatched_pixels += 1;
+            Rgb([255, 0, 0])
+        } else {
+            let grey = ((e.0[0] as u32 + e.0[1] as u32 + e.0[2] as u32) / 3 / 4) as u8;
+            Rgb([grey, grey, grey])
+        };
+    }
+
+    Ok(Comparison { max_difference, mismatched_pixels, diff })
+}
+
+/// Whether an offscreen context can be created on this machine
+pub fn headless_available() -> bool {
+    Headless::new(&WindowSettings { width: 1, height: 1, ..WindowSettings::default() }).is_ok()
+}
+
+/// A headless context for a test, or `None` after printing why the test is skipped. With `REQUIRE_HEADLESS=1`
+/// set a missing context fails the test instead, so CI can't pass without rendering anything
+///
+/// # Arguments
+///
+/// * `settings` the options the context is created with, always on the headless backend
+pub fn headless_or_skip(settings: &Settings) -> Option<Context> {
+    let settings = Settings { backend: Backend::Headless, ..settings.clone() };
+    match Context::new("test", &settings) {
+        Ok(ctx) => Some(ctx),
+        Err(e) if std::env::var_os(REQUIRE_HEADLESS_ENV).is_some_and(|value| value == "1") => {
+            panic!("{REQUIRE_HEADLESS_ENV} is set but no headless OpenGL context is available: {e}")
+        }
+        Err(e) => {
+            eprintln!("skipping: no headless OpenGL context available (needs EGL, e.g. Mesa llvmpipe): {e}");
+            None
+        }
+    }
+}
+
+/// Runs the exercise, or one of its variants, offscreen for `frames` frames with a fixed time step and returns the last frame
+pub fn render(runner: &dyn Runner, variant: Option<u32>, frames: u32) -> error::Result<RgbImage> {
+    let settings = Settings {
+        backend: Backend::Headless,
+        fixed_time_step: Some(TIME_STEP),
+        ..Settings::default()
+    };
+    let mut app = match variant {
+        Some(variant) if variant == 0 || variant as usize > runner.variants().len() => {
+            let id = format!("{}.{}.{variant}", runner.chapter(), runner.section());
+            return Err(error::Error::UnknownId { id, suggestions: Vec::new() });
+        }
+        Some(variant) => runner.variant_app(variant),
+        None => runner.app(),
+    };
+    app::render_frames(runner.name(), app.as_mut(), &settings, frames)
+}
+
+/// The file name of a runner's reference image, e.g. `1.3.hello_triangle.png` or `1.3.2.hello_triangle.png` for a variant
+pub fn reference_name(runner: &dyn Runner, variant: Option<u32>) -> String {
+    let variant = variant.map(|variant| format!(".{variant}")).unwrap_or_default();
+    format!("{}.{}{variant}.{}.png", runner.chapter(), runner.section(), runner.name().replace(' ', "_"))
+}
+
+/// Checks a rendered image against the reference `reference_dir/name`.
+///
+/// With `UPDATE_GOLDEN` set in the environment the reference is written instead.
+/// On a mismatch the actual and diff images are written to `output_dir` for inspection.
+///
+/// # Arguments
+///
+/// * `name` the file name of the reference image
+/// * `actual` the freshly rendered image
+/// * `reference_dir` the directory holding the checked in reference images
+/// * `output_dir` where to put the images describing a failure
+/// * `tolerance` how far `actual` may stray from the reference
+pub fn check(name: &str, actual: &RgbImage, reference_dir: &Path, output_dir: &Path, tolerance: &Tolerance) -> Result<(), String> {
+    let reference_path = reference_dir.join(name);
+
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        std::fs::create_dir_all(reference_dir).map_err(|e| format!("{name}: failed to create {}: {e}", reference_dir.display()))?;
+        return actual.save(&reference_path).map_err(|e| format!("{name}: failed to write {}: {e}", reference_path.display()));
+    }
+
+    let expected = image::open(&reference_path)
+        .map_err(|e| format!("{name}: failed to open {} ({e}), run with {UPDATE_ENV}=1 to create it", reference_path.display()))?
+        .to_rgb8();
+
+    let failure = match compare(actual, &expected, tolerance) {
+        Ok(comparison) if comparison.passed(tolerance) => return Ok(()),
+        Ok(comparison) => {
+            let diff_path = output_path(output_dir, name, "diff");
+            save_for_inspection(&comparison.diff, &diff_path);
+            format!("{name}: {} pixels differ by more than {} (max difference {}), diff written to {}",
+                    comparison.mismatched_pixels, tolerance.per_channel, comparison.max_difference, diff_path.display())
+        }
+        Err(e) => format!("{name}: {e}"),
+    };
+
+    let actual_path = output_path(output_dir, name, "actual");
+    save_for_inspection(actual, &actual_path);
+    Err(format!("{failure}, actual image written to {}", actual_path.display()))
+}
+
+/// `output_dir/1.3.hello_triangle.<suffix>.png`
+fn output_path(output_dir: &Path, name: &str, suffix: &str) -> PathBuf {
+    let stem = name.strip_suffix(".png").unwrap_or(name);
+    output_dir.join(format!("{stem}.{suffix}.png"))
+}
+
+/// Best effort write of an image that only helps diagnose a failure
+fn save_for_inspection(image: &RgbImage, path: &Path) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = image.save(path) {
+        eprintln!("Failed to write {}: {e}", path.display());
+    }
+}
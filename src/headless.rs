@@ -0,0 +1,172 @@
+use gl::types::GLuint;
+use khronos_egl as egl;
+use crate::app::{GlProfile, WindowSettings};
+use crate::error::{Error, Result};
+
+/// `EGL_PLATFORM_SURFACELESS_MESA` from `EGL_MESA_platform_surfaceless`
+const PLATFORM_SURFACELESS_MESA: egl::Enum = 0x31DD;
+
+/// A GL context with no window, rendering into an offscreen framebuffer.
+///
+/// Uses an EGL surfaceless display, so it works on machines without a GPU or X server
+/// as long as Mesa (llvmpipe) is installed.
+pub struct Headless {
+    egl: egl::DynamicInstance<egl::EGL1_5>,
+    display: egl::Display,
+    context: egl::Context,
+    width: u32,
+    height: u32,
+    framebuffer: GLuint,
+    color_buffer: GLuint,
+    depth_stencil_buffer: GLuint,
+    /// Single sampled copy of a multisampled `framebuffer` for reading back, as (framebuffer, color buffer)
+    resolve: Option<(GLuint, GLuint)>,
+}
+
+impl Headless {
+    /// Creates the requested context and makes a framebuffer of the requested size the current render target.
+    ///
+    /// The display mode, monitor and swap interval only apply to windows and are ignored.
+    pub fn new(settings: &WindowSettings) -> Result<Self> {
+        let (width, height) = (settings.width, settings.height);
+        let egl = unsafe { egl::DynamicInstance::<egl::EGL1_5>::load_required() }
+            .map_err(|e| Error::WindowCreation(format!("libEGL could not be loaded: {e}")))?;
+
+        let display = unsafe { egl.get_platform_display(PLATFORM_SURFACELESS_MESA, egl::DEFAULT_DISPLAY, &[egl::ATTRIB_NONE]) }
+            .map_err(|e| Error::WindowCreation(format!("no surfaceless EGL display: {e}")))?;
+        egl.initialize(display).map_err(|e| Error::WindowCreation(format!("EGL initialization failed: {e}")))?;
+        egl.bind_api(egl::OPENGL_API).map_err(|e| Error::WindowCreation(format!("binding the OpenGL API failed: {e}")))?;
+
+        // no surface will ever be created, so don't require the (default) window bit
+        let config_attributes = [egl::SURFACE_TYPE, 0, egl::RENDERABLE_TYPE, egl::OPENGL_BIT, egl::NONE];
+        let config = egl.choose_first_config(display, &config_attributes)
+            .map_err(|e| Error::WindowCreation(format!("choosing an EGL config failed: {e}")))?
+            .ok_or_else(|| Error::WindowCreation("no EGL config supports OpenGL".to_string()))?;
+
+        let (major, minor) = settings.gl_version;
+        let profile = match settings.gl_profile {
+            GlProfile::Core => egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+            GlProfile::Compatibility => egl::CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT,
+        };
+        let context_attributes = [
+            egl::CONTEXT_MAJOR_VERSION, major as egl::Int,
+            egl::CONTEXT_MINOR_VERSION, minor as egl::Int,
+            egl::CONTEXT_OPENGL_PROFILE_MASK, profile,
+            egl::NONE,
+        ];
+        let context = egl.create_context(display, config, None, &context_attributes)
+            .map_err(|e| Error::WindowCreation(format!("creating an OpenGL {major}.{minor} EGL context failed: {e}")))?;
+        egl.make_current(display, None, None, Some(context))
+            .map_err(|e| Error::WindowCreation(format!("making the EGL context current failed: {e}")))?;
+
+        // gl: load all OpenGL function pointers
+        gl::load_with(|symbol| egl.get_proc_address(symbol).map_or(std::ptr::null(), |f| f as *const _));
+        if !gl::GenFramebuffers::is_loaded() {
+            return Err(Error::GlLoad("framebuffer objects are not available, OpenGL 3.0 or later is required".to_string()));
+        }
+
+        let mut headless = Headless {
+            egl,
+            display,
+            context,
+            width,
+            height,
+            framebuffer: 0,
+            color_buffer: 0,
+            depth_stencil_buffer: 0,
+            resolve: None,
+        };
+        let samples = settings.samples.unwrap_or(0) as i32;
+
+        // there is no default framebuffer without a surface, so render into our own
+        unsafe {
+            gl::GenFramebuffers(1, &mut headless.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, headless.framebuffer);
+
+            gl::GenRenderbuffers(1, &mut headless.color_buffer);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, headless.color_buffer);
+            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::RGBA8, width as i32, height as i32);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, headless.color_buffer);
+
+            gl::GenRenderbuffers(1, &mut headless.depth_stencil_buffer);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, headless.depth_stencil_buffer);
+            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::DEPTH24_STENCIL8, width as i32, height as i32);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_STENCIL_ATTACHMENT, gl::RENDERBUFFER, headless.depth_stencil_buffer);
+
+            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                return Err(Error::WindowCreation("offscreen framebuffer is not complete".to_string()));
+            }
+
+            // multisampled renderbuffers can't be read directly, they are blitted into this one first
+            if samples > 0 {
+                let (mut resolve_framebuffer, mut resolve_color_buffer) = (0, 0);
+                gl::GenFramebuffers(1, &mut resolve_framebuffer);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, resolve_framebuffer);
+                gl::GenRenderbuffers(1, &mut resolve_color_buffer);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, resolve_color_buffer);
+                gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width as i32, height as i32);
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, resolve_color_buffer);
+                headless.resolve = Some((resolve_framebuffer, resolve_color_buffer));
+
+                if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                    return Err(Error::WindowCreation("offscreen resolve framebuffer is not complete".to_string()));
+                }
+                gl::BindFramebuffer(gl::FRAMEBUFFER, headless.framebuffer);
+            }
+
+            // without a surface the viewport starts out empty
+            gl::Viewport(0, 0, width as i32, height as i32);
+
+            // renderbuffer contents start out undefined, exercises that never clear should still capture the same image
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+        }
+
+        Ok(headless)
+    }
+
+    /// The size of the offscreen framebuffer in pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// The framebuffer standing in for the window's default framebuffer
+    pub fn framebuffer(&self) -> GLuint {
+        self.framebuffer
+    }
+
+    /// A single sampled framebuffer holding what has been rendered so far, ready for `glReadPixels`
+    pub fn resolve(&self) -> GLuint {
+        match self.resolve {
+            Some((resolve_framebuffer, _)) => unsafe {
+                let (width, height) = (self.width as i32, self.height as i32);
+                gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+                gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, resolve_framebuffer);
+                gl::BlitFramebuffer(0, 0, width, height, 0, 0, width, height, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+                resolve_framebuffer
+            },
+            None => self.framebuffer,
+        }
+    }
+}
+
+impl Drop for Headless {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteRenderbuffers(1, &self.color_buffer);
+            gl::DeleteRenderbuffers(1, &self.depth_stencil_buffer);
+            if let Some((resolve_framebuffer, resolve_color_buffer)) = self.resolve {
+                gl::DeleteFramebuffers(1, &resolve_framebuffer);
+                gl::DeleteRenderbuffers(1, &resolve_color_buffer);
+            }
+        }
+
+        // nothing useful can be done about failures while tearing down
+        let _ = self.egl.make_current(self.display, None, None, None);
+        let _ = self.egl.destroy_context(self.display, self.context);
+        // the display is shared by every context in the process, terminating it would pull it out from under
+        // contexts on other threads, so it stays initialized until the process exits
+    }
+}
@@ -0,0 +1,187 @@
+use glfw::{Action, Key};
+use crate::app::{App, Context};
+use crate::error::Result;
+use crate::lister::{Lister, RunID};
+
+/// Window title while the menu is shown
+const MENU_TITLE: &str = "learn_opengl";
+
+/// Space around the menu in pixels
+const MARGIN: i32 = 20;
+
+/// Height of a menu row in pixels, less when there are too many rows to fit
+const MAX_ROW_HEIGHT: i32 = 48;
+
+/// Hosts every registered exercise in a single window.
+///
+/// Starts on a menu navigated with the arrow keys, Enter starts the selected exercise and Escape goes back
+/// to the menu (or quits from the menu). Page Up and Page Down switch straight to the previous or next exercise.
+/// There is no text rendering yet, so the menu is drawn as a column of bars with the selected one highlighted,
+/// while the names are printed to the console and shown in the window title.
+pub struct Launcher {
+    lister: Lister,
+    /// Every run and variant, one per menu row
+    entries: Vec<RunID>,
+    /// `Lister::label` of each entry
+    labels: Vec<String>,
+    selected: usize,
+    running: Option<Box<dyn App>>,
+}
+
+impl Launcher {
+    /// Creates a launcher offering every run and variant in `lister`
+    pub fn new(lister: Lister) -> Self {
+        let entries = lister.ids();
+        let labels = entries.iter().map(|id| lister.label(id).expect("ids only holds listed runs")).collect();
+
+        Launcher { lister, entries, labels, selected: 0, running: None }
+    }
+
+    fn print_menu(&self) {
+        println!("Up/Down to choose, Enter to start, Escape to come back here or quit");
+        for (index, label) in self.labels.iter().enumerate() {
+            let marker = if index == self.selected { ">" } else { " " };
+            println!("{marker} {label}");
+        }
+    }
+
+    fn select(&mut self, ctx: &mut Context, index: usize) {
+        self.selected = index;
+        let label = &self.labels[index];
+        println!("> {label}");
+        ctx.set_title(&format!("{MENU_TITLE}: {label}"));
+    }
+
+    /// Starts the selected entry, staying on the menu if a `--param` doesn't fit it or its setup fails
+    fn start(&mut self, ctx: &mut Context) {
+        self.stop(ctx);
+
+        let label = &self.labels[self.selected];
+        let mut app = match self.lister.create_app(&self.entries[self.selected], ctx.param_overrides()) {
+            Ok(app) => app,
+            Err(e) => {
+                eprintln!("error: {label}: {e}");
+                return;
+            }
+        };
+
+        ctx.set_title(label);
+        match app.setup(ctx) {
+            Ok(_) => self.running = Some(app),
+            Err(e) => {
+                eprintln!("error: {label}: {e}");
+                // release whatever the failed setup managed to create
+                app.teardown(ctx);
+                ctx.reset_gl_state();
+                ctx.set_title(&format!("{MENU_TITLE}: {label}"));
+            }
+        }
+    }
+
+    /// Tears down the running exercise, if any, and goes back to the menu
+    fn stop(&mut self, ctx: &mut Context) {
+        if let Some(mut app) = self.running.take() {
+            app.teardown(ctx);
+            ctx.reset_gl_state();
+            ctx.set_title(&format!("{MENU_TITLE}: {}", self.labels[self.selected]));
+        }
+    }
+
+    fn render_menu(&self, ctx: &Context) {
+        let (width, height) = ctx.framebuffer_size();
+        let rows = self.entries.len().max(1) as i32;
+        let row_height = ((height - 2 * MARGIN) / rows).clamp(1, MAX_ROW_HEIGHT);
+        // rows are separated by a small gap when there is room for one
+        let gap = (row_height / 6).min(4);
+
+        unsafe {
+            gl::ClearColor(0.1, 0.1, 0.1, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::Enable(gl::SCISSOR_TEST);
+            for (index, entry) in self.entries.iter().enumerate() {
+                // variants are indented under their runner
+                let indent = if entry.variant().is_some() { 2 * MARGIN } else { 0 };
+                let top = height - MARGIN - index as i32 * row_height;
+                gl::Scissor(MARGIN + indent, top - row_height + gap, width - 2 * MARGIN - indent, row_height - gap);
+
+                if index == self.selected {
+                    gl::ClearColor(1.0, 0.5, 0.2, 1.0);
+                } else if matches!(entry, RunID::Numeric { section, .. } if section % 2 == 0) {
+                    gl::ClearColor(0.2, 0.3, 0.3, 1.0);
+                } else {
+                    gl::ClearColor(0.25, 0.25, 0.35, 1.0);
+                }
+                gl::Clear(gl::COLOR_BUFFER_BIT);
+            }
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+    }
+
+    fn on_menu_event(&mut self, ctx: &mut Context, event: &glfw::WindowEvent) {
+        let last = self.entries.len().saturating_sub(1);
+        match *event {
+            glfw::WindowEvent::Key(Key::Up, _, Action::Press | Action::Repeat, _) => self.select(ctx, self.selected.saturating_sub(1)),
+            glfw::WindowEvent::Key(Key::Down, _, Action::Press | Action::Repeat, _) => self.select(ctx, (self.selected + 1).min(last)),
+            glfw::WindowEvent::Key(Key::Home, _, Action::Press, _) => self.select(ctx, 0),
+            glfw::WindowEvent::Key(Key::End, _, Action::Press, _) => self.select(ctx, last),
+            glfw::WindowEvent::Key(Key::Enter | Key::KpEnter | Key::Space, _, Action::Press, _) => self.start(ctx),
+            _ => {}
+        }
+    }
+}
+
+impl App for Launcher {
+    fn setup(&mut self, ctx: &mut Context) -> Result<()> {
+        self.print_menu();
+        if let Some(first) = self.labels.first() {
+            ctx.set_title(&format!("{MENU_TITLE}: {first}"));
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut Context, delta_time: f32) {
+        if let Some(app) = &mut self.running {
+            app.update(ctx, delta_time);
+        }
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        match &mut self.running {
+            Some(app) => app.render(ctx),
+            None => self.render_menu(ctx),
+        }
+    }
+
+    fn on_event(&mut self, ctx: &mut Context, event: &glfw::WindowEvent) {
+        if self.running.is_none() {
+            self.on_menu_event(ctx, event);
+            return;
+        }
+
+        match *event {
+            glfw::WindowEvent::Key(key, _, Action::Press, _) if key == ctx.bindings().quit => {
+                // escape only leaves the exercise, the launcher keeps running
+                ctx.set_should_close(false);
+                self.stop(ctx);
+            }
+            glfw::WindowEvent::Key(Key::PageDown, _, Action::Press, _) if self.selected + 1 < self.entries.len() => {
+                self.selected += 1;
+                self.start(ctx);
+            }
+            glfw::WindowEvent::Key(Key::PageUp, _, Action::Press, _) if self.selected > 0 => {
+                self.selected -= 1;
+                self.start(ctx);
+            }
+            _ => {
+                if let Some(app) = &mut self.running {
+                    app.on_event(ctx, event);
+                }
+            }
+        }
+    }
+
+    fn teardown(&mut self, ctx: &mut Context) {
+        self.stop(ctx);
+    }
+}
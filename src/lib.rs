@@ -1,7 +1,23 @@
+pub mod error;
 pub mod runner;
+pub mod lister;
+pub mod app;
+pub mod config;
+pub mod clock;
+pub mod headless;
+pub mod screenshot;
+pub mod golden;
+pub mod params;
+pub mod launcher;
+pub mod slideshow;
+pub mod compare;
+pub mod scaffold;
+pub mod syllabus;
 
 pub mod exercises;
 
 pub mod shader;
+pub mod shader_error;
+pub mod uniform;
 pub mod common;
-pub mod camera;
\ No newline at end of file
+pub mod camera;
@@ -1,6 +1,10 @@
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
-use learn_opengl::*;
+use std::str::FromStr;
+use serde::Serialize;
+use crate::{app, runner};
+use crate::error::{Error, Result};
+use crate::params::{ParamSpec, Params};
 
 /// Keeps track of the available runs
 pub struct Lister {
@@ -8,59 +12,305 @@ pub struct Lister {
     exercises: Vec<Box<dyn runner::Runner>>,
 }
 
+/// A run as it appears in machine readable listings
+#[derive(Serialize)]
+struct ListEntry {
+    id: String,
+    chapter: i32,
+    section: i32,
+    name: &'static str,
+    #[serde(flatten)]
+    metadata: runner::Metadata,
+    variants: &'static [&'static str],
+    params: &'static [ParamSpec],
+}
+
+/// Suggestions are offered for names at most this many edits away, or a third of the name's length if longer
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// How many names to suggest at most
+const MAX_SUGGESTIONS: usize = 3;
+
 /// Possible ways to identify the run
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RunID {
-    Numeric { chapter: i32, section: i32 },
+    /// `CHAPTER.SECTION`, or `CHAPTER.SECTION.VARIANT` for one of the runner's variants
+    Numeric { chapter: i32, section: i32, variant: Option<u32> },
     Named(String),
 }
 
-/// Error for when the run is not found
-pub struct RunIDNotFound;
-
 impl Lister {
-    /// Creates a new list of the available runs for launching
+    /// Creates a new list of every run registered with `register_runner!`, ordered by chapter and section
     pub fn new() -> Self {
-        let exercises: Vec<Box<dyn runner::Runner>> = vec![
-            Box::new(exercises::creating_a_window::CreatingAWindow),
-            Box::new(exercises::hello_window::HelloWindow),
-            Box::new(exercises::hello_triangle::HelloTriangle),
-            Box::new(exercises::shader::Shader),
-            Box::new(exercises::textures::Textures),
-            Box::new(exercises::camera::Camera),
-        ];
+        let mut exercises: Vec<Box<dyn runner::Runner>> = inventory::iter::<runner::Registration>
+            .into_iter()
+            .map(|registration| (registration.create)())
+            .collect();
+        exercises.sort_by_key(|runner| (runner.chapter(), runner.section()));
 
         Lister { exercises }
     }
 
+    /// Describes every pair of runs sharing a chapter and section or a name, empty when all runs can be told apart
+    pub fn duplicates(&self) -> Vec<String> {
+        let runners: Vec<&dyn runner::Runner> = self.runners().collect();
+        let mut duplicates = Vec::new();
+
+        for (i, first) in runners.iter().enumerate() {
+            for second in &runners[i + 1..] {
+                if (first.chapter(), first.section()) == (second.chapter(), second.section()) {
+                    duplicates.push(format!("\"{}\" and \"{}\" are both {}.{}", first.name(), second.name(), first.chapter(), first.section()));
+                }
+                if normalize_name(first.name()) == normalize_name(second.name()) {
+                    duplicates.push(format!("{}.{} and {}.{} are both named \"{}\"", first.chapter(), first.section(), second.chapter(), second.section(), first.name()));
+                }
+            }
+        }
+
+        duplicates
+    }
+
+    /// The available runs ordered by chapter and section
+    pub fn runners(&self) -> impl Iterator<Item = &dyn runner::Runner> {
+        self.exercises.iter().map(|b| b.deref())
+    }
+
+    /// The available runs and their metadata as a pretty printed JSON array
+    pub fn to_json(&self) -> String {
+        let entries: Vec<ListEntry> = self.runners().map(|runner| ListEntry {
+            id: format!("{}.{}", runner.chapter(), runner.section()),
+            chapter: runner.chapter(),
+            section: runner.section(),
+            name: runner.name(),
+            metadata: runner.metadata(),
+            variants: runner.variants(),
+            params: runner.params(),
+        }).collect();
+
+        serde_json::to_string_pretty(&entries).expect("listing only holds strings and numbers")
+    }
+
+    /// The available runs as an aligned text table with one row per run
+    pub fn to_table(&self) -> String {
+        let header = ["ID", "NAME", "GL", "TAGS", "DESCRIPTION"].map(String::from);
+        let rows: Vec<[String; 5]> = self.runners().flat_map(|runner| {
+            let metadata = runner.metadata();
+            let row = [
+                format!("{}.{}", runner.chapter(), runner.section()),
+                runner.name().to_string(),
+                format!("{}.{}", metadata.gl_version.0, metadata.gl_version.1),
+                metadata.tags.join(", "),
+                metadata.description.to_string(),
+            ];
+            let variant_rows = runner.variants().iter().zip(1..).map(move |(description, variant)| [
+                format!("{}.{}.{variant}", runner.chapter(), runner.section()),
+                format!("  exercise {variant}"),
+                String::new(),
+                String::new(),
+                description.to_string(),
+            ]);
+            let param_rows = runner.params().iter().map(|param| [
+                String::new(),
+                format!("  param {}", param.name),
+                String::new(),
+                param.kind.to_string(),
+                format!("{} [default: {}]", param.description, param.default),
+            ]);
+            std::iter::once(row).chain(variant_rows).chain(param_rows)
+        }).collect();
+
+        let mut widths = header.clone().map(|column| column.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        std::iter::once(&header).chain(&rows).map(|row| {
+            let cells: Vec<String> = row.iter().zip(widths).map(|(cell, width)| format!("{cell:width$}")).collect();
+            cells.join("  ").trim_end().to_string()
+        }).collect::<Vec<String>>().join("\n")
+    }
+
     /// launches a run based on the id
     ///
     /// # Arguments
     ///
     /// * `id` the identification of which run to launch
-    pub fn launch(&self, id: RunID) -> Result<(), RunIDNotFound> {
+    /// * `settings` the options applied to the run
+    pub fn launch(&self, id: RunID, settings: &app::Settings) -> Result<()> {
+        let runner = self.find(&id)?;
+        // catch mistakes before a window opens
+        Params::resolve(runner.params(), &settings.params)?;
+
+        match id.variant() {
+            Some(variant) => runner.run_variant(variant, settings),
+            None => runner.run(settings),
+        }
+    }
+
+    /// Every run followed by each of its variants, in the order they are listed
+    pub fn ids(&self) -> Vec<RunID> {
+        self.runners().flat_map(|runner| {
+            let (chapter, section) = (runner.chapter(), runner.section());
+            let variants = (1..=runner.variants().len() as u32).map(Some);
+            std::iter::once(None).chain(variants).map(move |variant| RunID::Numeric { chapter, section, variant })
+        }).collect()
+    }
+
+    /// Every value the run argument accepts, for shell completion, with a short description of each.
+    /// Names are joined with `-` so they complete without quoting
+    pub fn completion_hints(&self) -> Vec<(String, String)> {
+        let ids = self.ids().into_iter().map(|id| {
+            let label = self.label(&id).expect("ids only holds listed runs");
+            (id.to_string(), label)
+        });
+        let names = self.runners().map(|runner| {
+            (runner.name().replace(' ', "-"), format!("{}.{}", runner.chapter(), runner.section()))
+        });
+        ids.chain(names).collect()
+    }
+
+    /// Creates the hooks for the run, or the variant of a run, matching the id
+    ///
+    /// # Arguments
+    ///
+    /// * `id` the identification of which run to create
+    /// * `overrides` the `--param` overrides the run will see, rejected if the run has no parameter of a name
+    pub fn create_app(&self, id: &RunID, overrides: &[(String, String)]) -> Result<Box<dyn app::App>> {
+        let runner = self.find(id)?;
+        Params::resolve(runner.params(), overrides)?;
+        Ok(match id.variant() {
+            Some(variant) => runner.variant_app(variant),
+            None => runner.app(),
+        })
+    }
+
+    /// A one line description of the run matching the id, e.g. `1.3.2 hello triangle (exercise 2)`
+    pub fn label(&self, id: &RunID) -> Result<String> {
+        let runner = self.find(id)?;
+        let variant = id.variant();
+        let number = match variant {
+            Some(variant) => format!("{}.{}.{variant}", runner.chapter(), runner.section()),
+            None => format!("{}.{}", runner.chapter(), runner.section()),
+        };
+        Ok(format!("{number} {}", runner.title(variant)))
+    }
+
+    /// Finds the run matching the id.
+    ///
+    /// Names are matched ignoring case and treating `-`, `_` and spaces alike, so `Hello-Triangle` finds
+    /// "hello triangle". A name that matches no run exactly may be the start of exactly one run's name.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` the identification of which run to find
+    pub fn find(&self, id: &RunID) -> Result<&dyn runner::Runner> {
         match id {
-            RunID::Numeric { chapter, section } => {
-                let matching_element = self.exercises.iter().find(|element| element.chapter() == chapter && element.section() == section);
-                match matching_element {
-                    Some(element) => {
-                        element.run();
-                        Ok(())
-                    }
-                    None => Err(RunIDNotFound)
+            RunID::Numeric { chapter, section, variant } => {
+                let found = self.runners()
+                    .find(|element| element.chapter() == *chapter && element.section() == *section)
+                    .ok_or_else(|| Error::UnknownId { id: id.to_string(), suggestions: Vec::new() })?;
+
+                match variant {
+                    Some(variant) if *variant as usize > found.variants().len() => Err(Error::UnknownId {
+                        id: id.to_string(),
+                        suggestions: (1..=found.variants().len()).map(|v| format!("{chapter}.{section}.{v}")).collect(),
+                    }),
+                    _ => Ok(found),
                 }
             }
             RunID::Named(name) => {
-                let matching_element = self.exercises.iter().find(|element| element.name() == name);
-                match matching_element {
-                    Some(element) => {
-                        element.run();
-                        Ok(())
-                    }
-                    None => Err(RunIDNotFound)
+                let wanted = normalize_name(name);
+                if let Some(exact) = self.runners().find(|element| normalize_name(element.name()) == wanted) {
+                    return Ok(exact);
+                }
+
+                let prefixed: Vec<&dyn runner::Runner> = self.runners().filter(|element| normalize_name(element.name()).starts_with(&wanted)).collect();
+                match prefixed.as_slice() {
+                    [only] => Ok(*only),
+                    [] => Err(Error::UnknownId { id: name.clone(), suggestions: self.suggestions(&wanted) }),
+                    several => Err(Error::AmbiguousId { id: name.clone(), matches: several.iter().map(|element| element.name().to_string()).collect() }),
                 }
             }
         }
     }
+
+    /// Names within a few typos of `wanted`, closest first
+    fn suggestions(&self, wanted: &str) -> Vec<String> {
+        let mut close: Vec<(usize, &'static str)> = self.runners()
+            .map(|element| (strsim::levenshtein(wanted, &normalize_name(element.name())), element.name()))
+            .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE.max(wanted.len() / 3))
+            .collect();
+        close.sort_by_key(|(distance, _)| *distance);
+        close.into_iter().take(MAX_SUGGESTIONS).map(|(_, name)| name.to_string()).collect()
+    }
+}
+
+impl Default for Lister {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunID {
+    /// The variant the id selects, if any
+    pub fn variant(&self) -> Option<u32> {
+        match self {
+            RunID::Numeric { variant, .. } => *variant,
+            RunID::Named(_) => None,
+        }
+    }
+}
+
+impl FromStr for RunID {
+    type Err = Error;
+
+    /// Parses `CHAPTER.SECTION`, `CHAPTER.SECTION.VARIANT` or a name.
+    ///
+    /// Anything starting with a digit is taken to be numeric, names never do.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: String| Error::InvalidId { id: s.to_string(), reason };
+
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(invalid("expected CHAPTER.SECTION or NAME".to_string()));
+        }
+        if !trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(RunID::Named(trimmed.to_string()));
+        }
+
+        let parts: Vec<&str> = trimmed.split('.').collect();
+        let (chapter, section, variant) = match parts.as_slice() {
+            [chapter, section] => (chapter, section, None),
+            [chapter, section, variant] => (chapter, section, Some(variant)),
+            [_] => return Err(invalid("expected CHAPTER.SECTION, e.g. 1.3".to_string())),
+            _ => return Err(invalid(format!("expected at most CHAPTER.SECTION.VARIANT but found {} parts", parts.len()))),
+        };
+
+        let chapter = chapter.parse::<i32>().map_err(|_| invalid(format!("chapter \"{chapter}\" is not a number")))?;
+        let section = section.parse::<i32>().map_err(|_| invalid(format!("section \"{section}\" is not a number")))?;
+        let variant = match variant {
+            Some(variant) => match variant.parse::<u32>() {
+                Ok(0) => return Err(invalid("variants count from 1".to_string())),
+                Ok(variant) => Some(variant),
+                Err(_) => return Err(invalid(format!("variant \"{variant}\" is not a number"))),
+            },
+            None => None,
+        };
+
+        Ok(RunID::Numeric { chapter, section, variant })
+    }
+}
+
+impl Display for RunID {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunID::Numeric { chapter, section, variant: Some(variant) } => write!(f, "{chapter}.{section}.{variant}"),
+            RunID::Numeric { chapter, section, variant: None } => write!(f, "{chapter}.{section}"),
+            RunID::Named(name) => write!(f, "{name}"),
+        }
+    }
 }
 
 impl Display for Lister {
@@ -73,5 +323,15 @@ fn describe_runner(runner: &dyn runner::Runner) -> String {
     let chapter = runner.chapter();
     let section = runner.section();
     let name = runner.name();
-    format!("Ch: {chapter}, S: {section}, Name: \"{name}\"")
+    let variants = runner.variants().iter().zip(1..).map(|(description, variant)| format!("\n    {chapter}.{section}.{variant}: {description}"));
+    format!("Ch: {chapter}, S: {section}, Name: \"{name}\"{}", variants.collect::<String>())
+}
+
+/// Lowercases a name and turns runs of `-`, `_` and whitespace into single spaces
+fn normalize_name(name: &str) -> String {
+    name.split(|c: char| c == '-' || c == '_' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
 }
@@ -1,50 +1,335 @@
-use clap::Parser;
-use crate::lister::RunID;
-
-mod lister;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use learn_opengl::app::{Backend, DisplayMode, GlProfile, Settings, WindowSettings};
+use learn_opengl::compare::{Compare, CompareMode};
+use learn_opengl::config::Config;
+use learn_opengl::app;
+use learn_opengl::error::Error;
+use learn_opengl::launcher::Launcher;
+use learn_opengl::scaffold::Scaffold;
+use learn_opengl::slideshow::Slideshow;
+use learn_opengl::syllabus;
+use learn_opengl::screenshot::ScheduledScreenshot;
+use learn_opengl::lister::{self, RunID};
+use learn_opengl::params;
 
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
-    /// Either CHAPTER.SECTION or NAME of exercise to run
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Either CHAPTER.SECTION[.VARIANT] or NAME of exercise to run, default_exercise in learn_opengl.toml when left out
     #[arg()]
-    arg: Option::<String>,
+    arg: Option::<RunID>,
+
+    /// Render into an offscreen framebuffer instead of a window (EGL surfaceless, e.g. Mesa llvmpipe)
+    #[arg(long, global = true)]
+    headless: bool,
+
+    /// Save a PNG of the framebuffer to this path and exit. F12 saves one at any time while running
+    #[arg(long, global = true, value_name = "PATH")]
+    screenshot: Option<PathBuf>,
+
+    /// Number of frames to render before taking the --screenshot
+    #[arg(long, global = true, value_name = "N", default_value_t = 1, requires = "screenshot", value_parser = clap::value_parser!(u32).range(1..))]
+    screenshot_after: u32,
+
+    /// Exit after rendering this many frames
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    frames: Option<u32>,
+
+    /// Exit after running for this many seconds
+    #[arg(long, global = true, value_name = "SECS", value_parser = app::parse_seconds)]
+    duration: Option<f64>,
+
+    /// Advance the clock by exactly this many seconds per frame instead of following the wall clock, so runs replay identically
+    #[arg(long, global = true, value_name = "SECS", value_parser = app::parse_seconds)]
+    time_step: Option<f64>,
+
+    /// Window width in screen coordinates (offscreen framebuffer width in pixels with --headless) [default: 800]
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    width: Option<u32>,
+
+    /// Window height in screen coordinates (offscreen framebuffer height in pixels with --headless) [default: 600]
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    height: Option<u32>,
+
+    /// Windowed, borderless fullscreen at the monitor's current resolution, or exclusive fullscreen at --width x --height
+    #[arg(long, global = true, value_enum, value_name = "MODE")]
+    display_mode: Option<DisplayMode>,
+
+    /// Index of the monitor to open the window on, 0 is the primary monitor
+    #[arg(long, global = true, value_name = "INDEX")]
+    monitor: Option<usize>,
+
+    /// Screen refreshes to wait for between frames, 0 disables vsync [default: 1]
+    #[arg(long, global = true, value_name = "N")]
+    swap_interval: Option<u32>,
+
+    /// Samples per pixel for multisample anti aliasing
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    samples: Option<u32>,
+
+    /// OpenGL version to request [default: 3.3]
+    #[arg(long, global = true, value_name = "MAJOR.MINOR", value_parser = app::parse_gl_version)]
+    gl_version: Option<(u32, u32)>,
+
+    /// OpenGL profile to request [default: core]
+    #[arg(long, global = true, value_enum, value_name = "PROFILE")]
+    gl_profile: Option<GlProfile>,
+
+    /// Set a parameter of the exercise, see `list` for the parameters each one has. Can be repeated
+    #[arg(long = "param", global = true, value_name = "NAME=VALUE", value_parser = params::parse_override)]
+    params: Vec<(String, String)>,
+
+    /// Read settings from this file instead of learn_opengl.toml in the working directory
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// List the available exercises
+    List {
+        /// How to print the list
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+    },
+    /// Open a window with a menu of every exercise and switch between them without restarting
+    Launcher,
+    /// Show every exercise in turn and report which ones crashed or failed to compile their shaders
+    RunAll {
+        /// How long to show each exercise, Space, Enter or Right skips ahead at any time
+        #[arg(long, value_name = "SECS", default_value_t = 3.0, conflicts_with = "manual")]
+        seconds: f64,
 
-fn main() {
-    let arg = Args::parse().arg;
+        /// Only move on to the next exercise on a key press
+        #[arg(long)]
+        manual: bool,
+    },
+    /// Show two exercises, or two variants of one, in the same window sharing input, Tab switches the layout
+    Compare {
+        /// CHAPTER.SECTION[.VARIANT] or NAME shown on the left
+        left: RunID,
+
+        /// CHAPTER.SECTION[.VARIANT] or NAME shown on the right
+        right: RunID,
+
+        /// How the window is shared at the start
+        #[arg(long, value_enum, default_value_t = CompareMode::SideBySide)]
+        mode: CompareMode,
+    },
+    /// Print a completion script for the shell, e.g. `learn_opengl completions bash > ~/.local/share/bash-completion/completions/learn_opengl`
+    Completions {
+        /// The shell to complete in
+        shell: Shell,
+    },
+    /// Show which pages of learnopengl.com have an exercise
+    Status {
+        /// Rewrite the checklist in readme.md of the working directory to match
+        #[arg(long)]
+        update_readme: bool,
+    },
+    /// Create a new exercise from the template, run from the root of the repository
+    New {
+        /// Chapter and section of the new exercise, e.g. 2.1
+        #[arg(value_name = "CHAPTER.SECTION")]
+        id: RunID,
+
+        /// Name of the new exercise, e.g. "basic lighting" or basic_lighting
+        name: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ListFormat {
+    /// Pretty printed JSON array with all metadata
+    Json,
+    /// Aligned columns for reading in a terminal
+    Table,
+}
+
+
+fn main() -> ExitCode {
+    let args = Args::parse();
     let runner_list = lister::Lister::new();
+    for duplicate in runner_list.duplicates() {
+        eprintln!("warning: {duplicate}");
+    }
 
-    if let Some(arg) = arg {
-        let id = if arg.contains('.') {
-            let args: Vec<&str> = arg.split('.').collect();
-            let chapter = args[0].parse::<i32>().expect("Period included in input with invalid Chapter ID before period.");
-            let section = args[1].parse::<i32>().expect("Period included in input with invalid Section ID after period.");
+    // command line flags win over the configuration files, which win over the built in defaults
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let defaults = config.settings();
+    let default_window = defaults.window;
+    let settings = Settings {
+        backend: if args.headless { Backend::Headless } else { Backend::Window },
+        window: WindowSettings {
+            width: args.width.unwrap_or(default_window.width),
+            height: args.height.unwrap_or(default_window.height),
+            display_mode: args.display_mode.unwrap_or(default_window.display_mode),
+            monitor: args.monitor.or(default_window.monitor),
+            swap_interval: args.swap_interval.unwrap_or(default_window.swap_interval),
+            samples: args.samples.or(default_window.samples),
+            gl_version: args.gl_version.unwrap_or(default_window.gl_version),
+            gl_profile: args.gl_profile.unwrap_or(default_window.gl_profile),
+        },
+        screenshot: args.screenshot.map(|path| ScheduledScreenshot { path, after_frames: args.screenshot_after }),
+        fixed_time_step: args.time_step,
+        frame_limit: args.frames,
+        duration_limit: args.duration,
+        params: args.params,
+        ..defaults
+    };
 
-            RunID::Numeric { chapter, section }
-        } else {
-            RunID::Named(arg)
-        };
+    match args.command {
+        Some(Command::List { format }) => {
+            match format {
+                ListFormat::Json => println!("{}", runner_list.to_json()),
+                ListFormat::Table => println!("{}", runner_list.to_table()),
+            }
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Launcher) => {
+            return match app::run("learn_opengl", &mut Launcher::new(runner_list), &settings) {
+                Ok(_) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Command::RunAll { seconds, manual }) => {
+            let mut slideshow = Slideshow::new(runner_list, if manual { None } else { Some(seconds) });
+            if let Err(e) = app::run("learn_opengl", &mut slideshow, &settings) {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+            println!("{}", slideshow.summary());
+            return if slideshow.has_failures() { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+        }
+        Some(Command::Compare { left, right, mode }) => {
+            let result = Compare::new(&runner_list, &left, &right, mode, &settings.params)
+                .and_then(|mut compare| app::run(&compare.title(), &mut compare, &settings));
+            return match result {
+                Ok(_) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Command::Completions { shell }) => {
+            print_completions(shell, &runner_list);
+            return ExitCode::SUCCESS;
+        }
+        Some(Command::Status { update_readme }) => {
+            println!("{}", syllabus::report(&runner_list));
+            if !update_readme {
+                return ExitCode::SUCCESS;
+            }
 
-        let run_result = runner_list.launch(id);
+            let readme_path = Path::new("readme.md");
+            let updated = std::fs::read_to_string(readme_path).map_err(|e| e.to_string()).and_then(|readme| {
+                syllabus::update_readme(&readme, &runner_list).ok_or_else(|| format!("the checklist markers are missing, add\n{}\n{}", syllabus::CHECKLIST_BEGIN, syllabus::CHECKLIST_END))
+            }).and_then(|readme| std::fs::write(readme_path, readme).map_err(|e| e.to_string()));
+            return match updated {
+                Ok(_) => {
+                    println!("updated {}", readme_path.display());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {}: {e}", readme_path.display());
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Command::New { id, name }) => {
+            return match Scaffold::new(&id, &name, &runner_list).and_then(|scaffold| scaffold.generate(Path::new("."))) {
+                Ok(files) => {
+                    for file in files {
+                        println!("wrote {}", file.display());
+                    }
+                    println!("run it with `cargo run -- {id}`, then add its reference image with `UPDATE_GOLDEN=1 cargo test --test golden`");
+                    println!("and tick it off in readme.md with `cargo run -- status --update-readme`");
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        None => {}
+    }
+
+    if let Some(arg) = args.arg.or(config.default_exercise) {
+        let run_result = runner_list.launch(arg, &settings);
 
         match run_result {
-            Ok(_) => { println!("========= DONE ==========") }
-            Err(_) => {
-                print_help_options(&runner_list);
+            Ok(_) => {
+                println!("========= DONE ==========");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                // without anything close to suggest, show everything that can be run
+                if matches!(&e, Error::UnknownId { suggestions, .. } if suggestions.is_empty()) {
+                    print_help_options(&runner_list);
+                }
+                ExitCode::FAILURE
             }
         }
     } else {
         print_help_options(&runner_list);
+        ExitCode::FAILURE
+    }
+}
+
+/// Prints the completion script with the registered ids and names as the values of the run argument
+fn print_completions(shell: Shell, list: &lister::Lister) {
+    let hints = list.completion_hints();
+    let values: Vec<PossibleValue> = hints.iter()
+        .map(|(value, help)| PossibleValue::new(value.clone()).help(help.clone()))
+        .collect();
+    // only the generated script sees the values, parsing still goes through RunID so prefixes keep working
+    let ids = PossibleValuesParser::new(values);
+    let mut command = Args::command()
+        .mut_arg("arg", |arg| arg.value_parser(ids.clone()))
+        .mut_subcommand("compare", |compare| {
+            compare.mut_arg("left", |arg| arg.value_parser(ids.clone())).mut_arg("right", |arg| arg.value_parser(ids.clone()))
+        });
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name.clone(), &mut std::io::stdout());
+
+    // clap_complete's fish generator skips positional arguments, so their possible values are completed here
+    // with fish's own helpers rather than the functions of the generated script
+    if shell == Shell::Fish {
+        let compare = command.find_subcommand("compare").expect("compare is a subcommand");
+        let positionals = std::iter::once(("__fish_use_subcommand", command.get_positionals().next()))
+            .chain([("__fish_seen_subcommand_from compare", compare.get_positionals().next())]);
+        for (condition, arg) in positionals {
+            for value in arg.expect("has a positional").get_possible_values() {
+                let help = value.get_help().map(|help| help.to_string().replace('\'', "\\'")).unwrap_or_default();
+                println!("complete -c {name} -n \"{condition}\" -f -a '{}' -d '{help}'", value.get_name());
+            }
+        }
     }
 }
 
 fn print_help_options(list: &lister::Lister) {
-    println!("ID not found");
     println!("usage: <arg>");
-    println!("arg: CHAPTER.SECTION or NAME");
+    println!("arg: CHAPTER.SECTION[.VARIANT] or NAME");
     println!();
     println!("Available Options:");
     println!("{}", list);
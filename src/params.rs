@@ -0,0 +1,169 @@
+use std::fmt::{Display, Formatter};
+use cgmath::{vec3, Vector3};
+use serde::Serialize;
+use crate::error::{Error, Result};
+
+/// The type of value a parameter holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    /// `true` or `false`
+    Bool,
+    /// A whole number
+    Int,
+    /// A decimal number
+    Float,
+    /// Any text, e.g. a file path
+    Text,
+    /// Points separated by `;`, each as `x,y,z`
+    Vec3List,
+}
+
+impl Display for ParamKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamKind::Bool => write!(f, "bool"),
+            ParamKind::Int => write!(f, "int"),
+            ParamKind::Float => write!(f, "float"),
+            ParamKind::Text => write!(f, "text"),
+            ParamKind::Vec3List => write!(f, "vec3 list"),
+        }
+    }
+}
+
+/// A named setting an exercise reads at setup, overridable with `--param name=value`
+#[derive(Clone, Debug, Serialize)]
+pub struct ParamSpec {
+    /// The key used on the command line
+    pub name: &'static str,
+    /// How the value is parsed
+    pub kind: ParamKind,
+    /// The value used when none is given, written as it would be on the command line
+    pub default: &'static str,
+    /// What the parameter changes
+    pub description: &'static str,
+}
+
+/// A parsed parameter value
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Text(String),
+    Vec3List(Vec<Vector3<f32>>),
+}
+
+/// The parameters of an exercise with the command line overrides applied
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    values: Vec<(&'static str, ParamValue)>,
+}
+
+impl Params {
+    /// Parses the defaults of `specs` and the overrides for them, overrides for other names are ignored
+    ///
+    /// # Arguments
+    ///
+    /// * `specs` the parameters the exercise declares
+    /// * `overrides` `(name, value)` pairs as given on the command line
+    pub fn resolve(specs: &[ParamSpec], overrides: &[(String, String)]) -> Result<Params> {
+        Self::check_names(specs, overrides)?;
+        let values = specs.iter().map(|spec| {
+            // the last override wins, like repeated flags usually do
+            let text = overrides.iter().rev().find(|(name, _)| name == spec.name).map_or(spec.default, |(_, value)| value.as_str());
+            parse(spec, text).map(|value| (spec.name, value))
+        }).collect::<Result<_>>()?;
+
+        Ok(Params { values })
+    }
+
+    /// Fails on the first override whose name is not one of `specs`
+    pub fn check_names(specs: &[ParamSpec], overrides: &[(String, String)]) -> Result<()> {
+        match overrides.iter().find(|(name, _)| !specs.iter().any(|spec| spec.name == name)) {
+            Some((name, _)) if specs.is_empty() => Err(Error::InvalidParam { name: name.clone(), reason: "this exercise has no parameters".to_string() }),
+            Some((name, _)) => {
+                let known: Vec<&str> = specs.iter().map(|spec| spec.name).collect();
+                Err(Error::InvalidParam { name: name.clone(), reason: format!("unknown parameter, expected one of {}", known.join(", ")) })
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The value of the `Bool` parameter `name`
+    pub fn bool(&self, name: &str) -> bool {
+        match self.get(name) {
+            ParamValue::Bool(value) => *value,
+            other => panic!("parameter {name} is not a bool but {other:?}"),
+        }
+    }
+
+    /// The value of the `Int` parameter `name`
+    pub fn int(&self, name: &str) -> i64 {
+        match self.get(name) {
+            ParamValue::Int(value) => *value,
+            other => panic!("parameter {name} is not an int but {other:?}"),
+        }
+    }
+
+    /// The value of the `Float` parameter `name`
+    pub fn float(&self, name: &str) -> f32 {
+        match self.get(name) {
+            ParamValue::Float(value) => *value,
+            other => panic!("parameter {name} is not a float but {other:?}"),
+        }
+    }
+
+    /// The value of the `Text` parameter `name`
+    pub fn text(&self, name: &str) -> &str {
+        match self.get(name) {
+            ParamValue::Text(value) => value,
+            other => panic!("parameter {name} is not text but {other:?}"),
+        }
+    }
+
+    /// The value of the `Vec3List` parameter `name`
+    pub fn vec3_list(&self, name: &str) -> &[Vector3<f32>] {
+        match self.get(name) {
+            ParamValue::Vec3List(value) => value,
+            other => panic!("parameter {name} is not a list of vectors but {other:?}"),
+        }
+    }
+
+    /// Parameters are declared next to the code reading them, so asking for an undeclared one is a bug
+    fn get(&self, name: &str) -> &ParamValue {
+        self.values.iter().find(|(key, _)| *key == name).map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("parameter {name} was not declared by the exercise"))
+    }
+}
+
+/// Parses the text of a parameter according to its kind
+fn parse(spec: &ParamSpec, text: &str) -> Result<ParamValue> {
+    let invalid = |expected: &str| Error::InvalidParam { name: spec.name.to_string(), reason: format!("expected {expected}, got \"{text}\"") };
+    let text = text.trim();
+
+    match spec.kind {
+        ParamKind::Bool => text.parse().map(ParamValue::Bool).map_err(|_| invalid("true or false")),
+        ParamKind::Int => text.parse().map(ParamValue::Int).map_err(|_| invalid("a whole number")),
+        ParamKind::Float => text.parse().map(ParamValue::Float).map_err(|_| invalid("a number")),
+        ParamKind::Text => Ok(ParamValue::Text(text.to_string())),
+        ParamKind::Vec3List => text.split(';')
+            .filter(|point| !point.trim().is_empty())
+            .map(|point| {
+                let coordinates: Vec<f32> = point.split(',').map(|c| c.trim().parse()).collect::<std::result::Result<_, _>>()
+                    .map_err(|_| invalid("points like 1,2,3;4,5,6"))?;
+                match coordinates.as_slice() {
+                    [x, y, z] => Ok(vec3(*x, *y, *z)),
+                    _ => Err(invalid("three coordinates per point like 1,2,3;4,5,6")),
+                }
+            })
+            .collect::<Result<_>>()
+            .map(ParamValue::Vec3List),
+    }
+}
+
+/// Splits a `name=value` command line argument
+pub fn parse_override(argument: &str) -> std::result::Result<(String, String), String> {
+    let (name, value) = argument.split_once('=').ok_or_else(|| format!("expected NAME=VALUE, got \"{argument}\""))?;
+    Ok((name.trim().to_string(), value.to_string()))
+}
@@ -1,3 +1,68 @@
+use serde::Serialize;
+use crate::app::{self, App, Settings};
+use crate::error::Result;
+use crate::params::ParamSpec;
+
+/// Descriptive information about a run used for listings and documentation
+#[derive(Clone, Debug, Serialize)]
+pub struct Metadata {
+    /// One line summary of what the example shows
+    pub description: &'static str,
+    /// The learnopengl.com page the example follows
+    pub url: Option<&'static str>,
+    /// Topics covered, e.g. "textures" or "input"
+    pub tags: &'static [&'static str],
+    /// The lowest OpenGL version the example needs as (major, minor)
+    #[serde(serialize_with = "serialize_version")]
+    pub gl_version: (u8, u8),
+    /// Files the example loads at runtime, relative to the working directory
+    pub assets: &'static [&'static str],
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Metadata {
+            description: "",
+            url: None,
+            tags: &[],
+            gl_version: (3, 3),
+            assets: &[],
+        }
+    }
+}
+
+/// An entry in the compile time list of runs, created by `register_runner!`
+pub struct Registration {
+    /// Creates the runner when the list of runs is built
+    pub create: fn() -> Box<dyn Runner>,
+}
+
+inventory::collect!(Registration);
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Makes a runner launchable and listable without touching `Lister`.
+///
+/// Put it next to the `Runner` impl, the argument is an expression creating the runner.
+///
+/// ```ignore
+/// register_runner!(HelloTriangle);
+/// ```
+#[macro_export]
+macro_rules! register_runner {
+    ($runner:expr) => {
+        $crate::runner::inventory::submit! {
+            $crate::runner::Registration { create: || Box::new($runner) }
+        }
+    };
+}
+
+/// Writes a (major, minor) version as "major.minor"
+fn serialize_version<S: serde::Serializer>(version: &(u8, u8), serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{}.{}", version.0, version.1))
+}
+
 /// Defines the properties of a given run.  Allows for identifiying the attempt in a few ways
 pub trait Runner {
     /// The Chapter Number for this Runner
@@ -9,6 +74,46 @@ pub trait Runner {
     /// The name for this Runner
     fn name(&self) -> &'static str;
 
+    /// Creates the hooks the shared render loop drives for this example
+    fn app(&self) -> Box<dyn App>;
+
+    /// Names of alternative versions of the example, such as the exercises at the end of a chapter.
+    ///
+    /// They are launched with `CHAPTER.SECTION.VARIANT`, counting from 1.
+    fn variants(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Creates the hooks for one of the `variants`, `variant` counts from 1 and is never past the last variant
+    fn variant_app(&self, _variant: u32) -> Box<dyn App> {
+        self.app()
+    }
+
+    /// The parameters the example reads with `Context::params`, which can be set with `--param name=value`
+    fn params(&self) -> &'static [ParamSpec] {
+        &[]
+    }
+
+    /// Optional description of the example, empty unless overridden
+    fn metadata(&self) -> Metadata {
+        Metadata::default()
+    }
+
     /// Run the example.
-    fn run(&self);
+    fn run(&self, settings: &Settings) -> Result<()> {
+        app::run(self.name(), self.app().as_mut(), settings)
+    }
+
+    /// Run one of the `variants`, `variant` counts from 1
+    fn run_variant(&self, variant: u32, settings: &Settings) -> Result<()> {
+        app::run(&self.title(Some(variant)), self.variant_app(variant).as_mut(), settings)
+    }
+
+    /// The window title for the example or one of its `variants`
+    fn title(&self, variant: Option<u32>) -> String {
+        match variant {
+            Some(variant) => format!("{} (exercise {variant})", self.name()),
+            None => self.name().to_string(),
+        }
+    }
 }
@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+use crate::lister::{Lister, RunID};
+
+/// The runner module every new exercise starts from
+const RUNNER_TEMPLATE: &str = include_str!("../templates/runner.rs.in");
+
+/// The header of the template explaining the placeholders, dropped from generated files
+const TEMPLATE_NOTE: &str = "// The template `learn_opengl new CHAPTER.SECTION NAME` fills in";
+
+/// Strict and reserved Rust keywords, which can't name a module or a struct
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn", "else",
+    "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+    "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Names from the prelude the template uses, a runner struct with one of them would shadow it
+const PRELUDE_NAMES: &[&str] = &["Box", "Default", "None", "Ok", "Option", "Some"];
+
+const VERTEX_SHADER_TEMPLATE: &str = "#version 330 core
+layout (location = 0) in vec3 aPos;
+
+void main() {
+    gl_Position = vec4(aPos, 1.0);
+}
+";
+
+const FRAGMENT_SHADER_TEMPLATE: &str = "#version 330 core
+out vec4 FragColor;
+
+uniform float time;
+
+void main() {
+    FragColor = vec4(1.0, 0.5 + 0.5 * sin(time), 0.2, 1.0);
+}
+";
+
+/// A new exercise to generate from the template: a runner module, its shader pair and the `pub mod` line
+/// registering it in `src/exercises/mod.rs`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scaffold {
+    chapter: i32,
+    section: i32,
+    /// Words of the name in lower case, e.g. `["light", "casters"]`
+    words: Vec<String>,
+}
+
+impl Scaffold {
+    /// Checks that the id and name are well formed and not used by a registered exercise
+    ///
+    /// # Arguments
+    ///
+    /// * `id` the CHAPTER.SECTION of the new exercise, without a variant
+    /// * `name` the name of the new exercise, words separated by spaces, `-` or `_`
+    /// * `lister` the exercises the new one must not clash with
+    pub fn new(id: &RunID, name: &str, lister: &Lister) -> Result<Scaffold> {
+        let &RunID::Numeric { chapter, section, variant: None } = id else {
+            return Err(Error::InvalidId { id: id.to_string(), reason: "expected CHAPTER.SECTION".to_string() });
+        };
+
+        let words: Vec<String> = name.split(|c: char| c == '-' || c == '_' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let invalid_name = |reason: &str| Error::InvalidId { id: name.to_string(), reason: reason.to_string() };
+        if words.is_empty() {
+            return Err(invalid_name("the name is empty"));
+        }
+        if !words.iter().flat_map(|word| word.chars()).all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid_name("names may only use letters, digits, spaces, - and _"));
+        }
+        if words[0].starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(invalid_name("names must start with a letter"));
+        }
+
+        let scaffold = Scaffold { chapter, section, words };
+        if KEYWORDS.contains(&scaffold.module().as_str()) {
+            return Err(invalid_name(&format!("\"{}\" is a Rust keyword", scaffold.module())));
+        }
+        let type_names = [scaffold.type_name(), format!("{}App", scaffold.type_name())];
+        if let Some(clash) = type_names.iter().find(|type_name| reserved_type_names().any(|reserved| reserved == type_name.as_str())) {
+            return Err(invalid_name(&format!("the runner type \"{clash}\" would clash with a name the template uses")));
+        }
+
+        let taken = lister.runners().find(|runner| {
+            (runner.chapter(), runner.section()) == (chapter, section) || runner.name() == scaffold.name()
+        });
+        if let Some(runner) = taken {
+            return Err(Error::IdTaken { id: format!("{id} {}", scaffold.name()), by: format!("{}.{} {}", runner.chapter(), runner.section(), runner.name()) });
+        }
+
+        Ok(scaffold)
+    }
+
+    /// The name the runner reports, e.g. `light casters`
+    pub fn name(&self) -> String {
+        self.words.join(" ")
+    }
+
+    /// The module and file name, e.g. `light_casters`
+    pub fn module(&self) -> String {
+        self.words.join("_")
+    }
+
+    /// The runner struct name, e.g. `LightCasters`
+    pub fn type_name(&self) -> String {
+        self.words.iter().map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        }).collect()
+    }
+
+    /// The stem of the shader files, e.g. `2.5.light_casters` for `shaders/2.5.light_casters.vert`
+    pub fn shader_stem(&self) -> String {
+        format!("{}.{}.{}", self.chapter, self.section, self.module())
+    }
+
+    /// Writes the new files below `root` and adds the module to `src/exercises/mod.rs`, nothing is written if any
+    /// of the files already exists. Returns the paths of the created and changed files
+    ///
+    /// # Arguments
+    ///
+    /// * `root` the root of the learn_opengl checkout, holding `src/` and `shaders/`
+    pub fn generate(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let mod_path = root.join("src/exercises/mod.rs");
+        let mod_rs = std::fs::read_to_string(&mod_path).map_err(|source| Error::AssetIo { path: mod_path.clone(), source })?;
+
+        let files = [
+            (root.join("src/exercises").join(format!("{}.rs", self.module())), self.runner_source()),
+            (root.join("shaders").join(format!("{}.vert", self.shader_stem())), VERTEX_SHADER_TEMPLATE.to_string()),
+            (root.join("shaders").join(format!("{}.frag", self.shader_stem())), FRAGMENT_SHADER_TEMPLATE.to_string()),
+        ];
+        if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+            return Err(Error::AssetIo { path: path.clone(), source: std::io::ErrorKind::AlreadyExists.into() });
+        }
+
+        for (path, contents) in &files {
+            std::fs::write(path, contents).map_err(|source| Error::AssetIo { path: path.clone(), source })?;
+        }
+
+        let separator = if mod_rs.is_empty() || mod_rs.ends_with('\n') { "" } else { "\n" };
+        let mod_rs = format!("{mod_rs}{separator}pub mod {};\n", self.module());
+        std::fs::write(&mod_path, mod_rs).map_err(|source| Error::AssetIo { path: mod_path.clone(), source })?;
+
+        Ok(files.into_iter().map(|(path, _)| path).chain([mod_path]).collect())
+    }
+
+    /// The template with the placeholders filled in
+    fn runner_source(&self) -> String {
+        // the note ends at the first blank line after it
+        let start = RUNNER_TEMPLATE.find(TEMPLATE_NOTE).expect("the template starts with a note");
+        let end = RUNNER_TEMPLATE[start..].find("\n\n").map_or(start, |offset| start + offset + 2);
+
+        format!("{}{}", &RUNNER_TEMPLATE[..start], &RUNNER_TEMPLATE[end..])
+            .replace("__Type__", &self.type_name())
+            .replace("__CHAPTER__", &self.chapter.to_string())
+            .replace("__SECTION__", &self.section.to_string())
+            .replace("__NAME__", &self.name())
+            .replace("__SHADER__", &self.shader_stem())
+    }
+}
+
+/// The names the template imports with `use` and the prelude names it relies on
+fn reserved_type_names() -> impl Iterator<Item = &'static str> {
+    let imports = RUNNER_TEMPLATE.lines()
+        .filter_map(|line| line.strip_prefix("use "))
+        .flat_map(|path| {
+            let names = path.rsplit_once("::").map_or(path, |(_, names)| names);
+            names.trim_end_matches(';').trim_matches(['{', '}']).split(',').map(str::trim)
+        });
+    imports.chain(PRELUDE_NAMES.iter().copied())
+}
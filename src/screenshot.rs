@@ -0,0 +1,58 @@
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use gl::types::GLuint;
+use image::RgbImage;
+
+/// A screenshot to take after a number of rendered frames, ending the run once written
+#[derive(Clone, Debug)]
+pub struct ScheduledScreenshot {
+    /// Where the PNG is written
+    pub path: PathBuf,
+    /// How many frames to render before capturing
+    pub after_frames: u32,
+}
+
+/// Reads the color buffer of `framebuffer` into an image with the top row first
+///
+/// # Arguments
+///
+/// * `framebuffer` the framebuffer to read, 0 for the window's back buffer
+/// * `width` `height` the size of the region to read, starting at the bottom left corner
+pub fn capture(framebuffer: GLuint, width: u32, height: u32) -> RgbImage {
+    let mut pixels = vec![0u8; (width * height * 3) as usize];
+    unsafe {
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+        // rows are tightly packed, 3 bytes per pixel is not 4 byte aligned
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(0,
+                       0,
+                       width as i32,
+                       height as i32,
+                       gl::RGB,
+                       gl::UNSIGNED_BYTE,
+                       pixels.as_mut_ptr() as *mut c_void);
+    }
+
+    let image = RgbImage::from_raw(width, height, pixels).expect("pixel buffer matches the image size");
+    // OpenGL's origin is the bottom left, images start at the top left
+    image::imageops::flip_vertical(&image)
+}
+
+/// Writes the image to `path`, the format is chosen from the extension
+pub fn save(image: &RgbImage, path: &Path) -> image::ImageResult<()> {
+    image.save(path)
+}
+
+/// A file name for a screenshot taken now, used by the hotkey. The milliseconds keep runs apart and the frame
+/// keeps captures of one run apart however quickly they follow each other
+///
+/// # Arguments
+///
+/// * `prefix` the start of the name, usually the window title
+/// * `frame` the number of the frame being captured
+pub fn timestamped_path(prefix: &str, frame: u32) -> PathBuf {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+    let prefix = prefix.replace(' ', "_");
+    PathBuf::from(format!("{prefix}-{millis}-frame{frame}.png"))
+}
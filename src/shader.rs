@@ -1,10 +1,65 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::CString;
-use std::io::Read;
-use cgmath::{Matrix, Matrix4};
-use gl::types::{GLchar, GLint, GLuint};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use gl::types::{GLbitfield, GLchar, GLenum, GLint, GLsizei, GLuint};
+use crate::error::{Error, Result};
+use crate::shader_error::ShaderError;
+use crate::uniform::UniformValue;
+
+/// A programmable stage of the pipeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShaderStage {
+    Vertex,
+    TessControl,
+    TessEvaluation,
+    Geometry,
+    Fragment,
+    Compute,
+}
+
+impl ShaderStage {
+    /// The shader type passed to `glCreateShader`
+    pub fn gl_type(self) -> GLenum {
+        match self {
+            ShaderStage::Vertex => gl::VERTEX_SHADER,
+            ShaderStage::TessControl => gl::TESS_CONTROL_SHADER,
+            ShaderStage::TessEvaluation => gl::TESS_EVALUATION_SHADER,
+            ShaderStage::Geometry => gl::GEOMETRY_SHADER,
+            ShaderStage::Fragment => gl::FRAGMENT_SHADER,
+            ShaderStage::Compute => gl::COMPUTE_SHADER,
+        }
+    }
+
+    /// The oldest OpenGL version with the stage as (major, minor)
+    pub fn min_gl_version(self) -> (u32, u32) {
+        match self {
+            ShaderStage::Vertex | ShaderStage::Fragment => (2, 0),
+            ShaderStage::Geometry => (3, 2),
+            ShaderStage::TessControl | ShaderStage::TessEvaluation => (4, 0),
+            ShaderStage::Compute => (4, 3),
+        }
+    }
+}
+
+impl Display for ShaderStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::TessControl => write!(f, "tessellation control"),
+            ShaderStage::TessEvaluation => write!(f, "tessellation evaluation"),
+            ShaderStage::Geometry => write!(f, "geometry"),
+            ShaderStage::Fragment => write!(f, "fragment"),
+            ShaderStage::Compute => write!(f, "compute"),
+        }
+    }
+}
 
 pub struct Shader {
     uid: GLuint,
+    /// Uniform locations looked up so far, -1 for names the program doesn't have
+    locations: RefCell<HashMap<String, GLint>>,
 }
 
 impl Shader {
@@ -12,11 +67,17 @@ impl Shader {
         self.uid
     }
 
-    pub fn new(vertex_path: &std::path::Path, fragment_path: &std::path::Path) -> Self {
-        let vertex_shader = read_file_to_cstring(vertex_path);
-        let fragment_shader = read_file_to_cstring(fragment_path);
+    pub fn new(vertex_path: &Path, fragment_path: &Path) -> Result<Self> {
+        ShaderBuilder::vertex(vertex_path).fragment(fragment_path).build()
+    }
 
-        Shader { uid: compile_shader_program(&vertex_shader, &fragment_shader) }
+    /// Builds a program from GLSL source held in memory instead of files
+    pub fn from_source(vertex_source: &str, fragment_source: &str) -> Result<Self> {
+        ShaderBuilder::vertex_source(vertex_source).fragment_source(fragment_source).build()
+    }
+
+    fn from_program(uid: GLuint) -> Self {
+        Shader { uid, locations: RefCell::new(HashMap::new()) }
     }
 
     pub fn use_program(&self) {
@@ -25,89 +86,346 @@ impl Shader {
         }
     }
 
-    // Utility uniform functions
-    pub fn set_bool(&self, name: &CString, value: bool) {
-        unsafe {
-            gl::Uniform1i(gl::GetUniformLocation(self.uid, name.as_ptr()), value as i32);
+    /// The location of a uniform, looked up once per name. Uniforms the program doesn't have, because they
+    /// are misspelled or the compiler optimized them out, get -1 which OpenGL ignores, and a warning the first time
+    pub fn uniform_location(&self, name: &str) -> GLint {
+        if let Some(&location) = self.locations.borrow().get(name) {
+            return location;
         }
-    }
 
-    pub fn set_int(&self, name: &CString, value: i32) {
-        unsafe {
-            gl::Uniform1i(gl::GetUniformLocation(self.uid, name.as_ptr()), value);
+        let location = match CString::new(name) {
+            Ok(c_name) => unsafe { gl::GetUniformLocation(self.uid, c_name.as_ptr()) },
+            Err(_) => -1,
+        };
+        if location == -1 {
+            eprintln!("warning: shader program {} has no active uniform \"{name}\", it is misspelled or optimized out", self.uid);
         }
+        self.locations.borrow_mut().insert(name.to_string(), location);
+        location
     }
 
-    pub fn set_float(&self, name: &CString, value: f32) {
-        unsafe {
-            gl::Uniform1f(gl::GetUniformLocation(self.uid, name.as_ptr()), value);
-        }
+    /// The names looked up with `uniform_location` that the program has no active uniform for, sorted
+    pub fn missing_uniforms(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.locations.borrow().iter()
+            .filter(|(_, &location)| location == -1)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
     }
 
-    pub fn set_mat4(&self, name: &CString, value: &Matrix4<f32>) {
-        unsafe {
-            gl::UniformMatrix4fv(gl::GetUniformLocation(self.uid, name.as_ptr()), 1, gl::FALSE, value.as_ptr());
+    /// Sets a uniform of the program, which must be in use
+    ///
+    /// # Arguments
+    ///
+    /// * `name` the uniform's name in the GLSL source, the first element for arrays
+    /// * `value` a value of the uniform's type, a `Sampler` for samplers and a slice or array for arrays
+    pub fn set(&self, name: &str, value: impl UniformValue) {
+        value.upload(self.uniform_location(name));
+    }
+}
+
+impl Drop for Shader {
+    /// Deletes the program, so an exercise's `teardown` only has to drop its shaders. Nothing is deleted once the
+    /// `Context` is gone, dropping a shader then has no context to reach
+    fn drop(&mut self) {
+        if crate::app::context_current() {
+            unsafe { gl::DeleteProgram(self.uid) };
         }
     }
 }
 
-fn read_file_to_cstring(path: &std::path::Path) -> CString {
-    #[allow(clippy::expect_fun_call)]
-    let mut file = std::fs::File::open(path).expect(&format!("Failed to open: {}", path.display()));
-    let mut text = std::default::Default::default();
-    #[allow(clippy::expect_fun_call)]
-    file.read_to_string(&mut text).expect(&format!("Failed to read: {}", path.display()));
+/// Where the GLSL of a stage comes from
+enum StageCode {
+    File(PathBuf),
+    Source(String),
+}
 
-    #[allow(clippy::expect_fun_call)]
-    CString::new(text.as_bytes()).expect(&format!("Failed to convert: {}", path.display()))
+/// Collects the stages of a program, e.g. `ShaderBuilder::vertex(path).geometry(path).fragment(path).build()`.
+/// Files are only read when the program is built. Compute programs are built with `ComputeShader` instead
+pub struct ShaderBuilder {
+    stages: Vec<(ShaderStage, StageCode)>,
 }
 
-fn compile_shader_program(vertex_shader: &CString, fragment_shader: &CString) -> GLuint {
-    let id;
-    unsafe {
-        let vertex = gl::CreateShader(gl::VERTEX_SHADER);
-        gl::ShaderSource(vertex, 1, &vertex_shader.as_ptr(), std::ptr::null());
-        gl::CompileShader(vertex);
-        check_compile_errors(vertex, "VERTEX");
+impl ShaderBuilder {
+    /// Starts a program with the vertex stage read from `path`
+    pub fn vertex(path: impl AsRef<Path>) -> Self {
+        ShaderBuilder { stages: Vec::new() }.stage(ShaderStage::Vertex, StageCode::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Starts a program with the vertex stage from GLSL source held in memory
+    pub fn vertex_source(source: &str) -> Self {
+        ShaderBuilder { stages: Vec::new() }.stage(ShaderStage::Vertex, StageCode::Source(source.to_string()))
+    }
+
+    /// Adds a tessellation control stage read from `path`, it needs a tessellation evaluation stage as well
+    pub fn tess_control(self, path: impl AsRef<Path>) -> Self {
+        self.stage(ShaderStage::TessControl, StageCode::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Adds a tessellation control stage from GLSL source held in memory
+    pub fn tess_control_source(self, source: &str) -> Self {
+        self.stage(ShaderStage::TessControl, StageCode::Source(source.to_string()))
+    }
+
+    /// Adds a tessellation evaluation stage read from `path`, draw with `gl::PATCHES` when the program has one
+    pub fn tess_evaluation(self, path: impl AsRef<Path>) -> Self {
+        self.stage(ShaderStage::TessEvaluation, StageCode::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Adds a tessellation evaluation stage from GLSL source held in memory
+    pub fn tess_evaluation_source(self, source: &str) -> Self {
+        self.stage(ShaderStage::TessEvaluation, StageCode::Source(source.to_string()))
+    }
+
+    /// Adds a geometry stage read from `path`
+    pub fn geometry(self, path: impl AsRef<Path>) -> Self {
+        self.stage(ShaderStage::Geometry, StageCode::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Adds a geometry stage from GLSL source held in memory
+    pub fn geometry_source(self, source: &str) -> Self {
+        self.stage(ShaderStage::Geometry, StageCode::Source(source.to_string()))
+    }
+
+    /// Adds the fragment stage read from `path`
+    pub fn fragment(self, path: impl AsRef<Path>) -> Self {
+        self.stage(ShaderStage::Fragment, StageCode::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Adds the fragment stage from GLSL source held in memory
+    pub fn fragment_source(self, source: &str) -> Self {
+        self.stage(ShaderStage::Fragment, StageCode::Source(source.to_string()))
+    }
+
+    /// Reads, compiles and links the stages, stages are compiled in pipeline order so the first error reported
+    /// is the earliest in the pipeline
+    pub fn build(self) -> Result<Shader> {
+        let mut stages = self.stages.into_iter()
+            .map(|(stage, code)| StageSource::new(stage, code))
+            .collect::<Result<Vec<StageSource>>>()?;
+        stages.sort_by_key(|source| source.stage);
+
+        Ok(Shader::from_program(compile_shader_program(&stages)?))
+    }
+
+    /// Sets a stage, replacing an earlier one of the same kind
+    fn stage(mut self, stage: ShaderStage, code: StageCode) -> Self {
+        self.stages.retain(|(existing, _)| *existing != stage);
+        self.stages.push((stage, code));
+        self
+    }
+}
+
+/// A program with a single compute stage, run with `dispatch` outside of the render pipeline. Needs OpenGL 4.3
+///
+/// Derefs to `Shader` for setting uniforms, the program is deleted when the inner `Shader` is dropped.
+pub struct ComputeShader {
+    shader: Shader,
+}
+
+impl ComputeShader {
+    /// Builds the program from a file
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        ComputeShader::build(StageCode::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Builds the program from GLSL source held in memory instead of a file
+    pub fn from_source(source: &str) -> Result<Self> {
+        ComputeShader::build(StageCode::Source(source.to_string()))
+    }
+
+    fn build(code: StageCode) -> Result<Self> {
+        let stage = StageSource::new(ShaderStage::Compute, code)?;
+        Ok(ComputeShader { shader: Shader::from_program(compile_shader_program(&[stage])?) })
+    }
+
+    /// The `local_size_x`, `local_size_y` and `local_size_z` the shader declares
+    pub fn work_group_size(&self) -> [u32; 3] {
+        let mut size: [GLint; 3] = [1; 3];
+        unsafe { gl::GetProgramiv(self.shader.uid, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr()) };
+        size.map(|length| length.max(1) as u32)
+    }
+
+    /// Uses the program and runs it for a grid of work groups
+    ///
+    /// # Arguments
+    ///
+    /// * `groups` the number of work groups along x, y and z
+    pub fn dispatch(&self, groups: [u32; 3]) {
+        self.shader.use_program();
+        unsafe { gl::DispatchCompute(groups[0], groups[1], groups[2]) };
+    }
+
+    /// Uses the program and runs enough work groups for at least one invocation per item, e.g. per texel of an image.
+    /// The shader has to skip the invocations past the end when the size isn't a multiple of the work group size
+    ///
+    /// # Arguments
+    ///
+    /// * `items` the number of items along x, y and z
+    pub fn dispatch_for(&self, items: [u32; 3]) {
+        let size = self.work_group_size();
+        self.dispatch([0, 1, 2].map(|axis| items[axis].div_ceil(size[axis])));
+    }
+}
+
+impl std::ops::Deref for ComputeShader {
+    type Target = Shader;
+
+    fn deref(&self) -> &Shader {
+        &self.shader
+    }
+}
+
+/// What writes from shaders through images and storage buffers have to be visible to, see `memory_barrier`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Barrier(GLbitfield);
+
+impl Barrier {
+    /// Vertex attributes sourced from buffers
+    pub const VERTEX_ATTRIB_ARRAY: Barrier = Barrier(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    /// Index buffers of indexed draws
+    pub const ELEMENT_ARRAY: Barrier = Barrier(gl::ELEMENT_ARRAY_BARRIER_BIT);
+    /// Uniform buffers
+    pub const UNIFORM: Barrier = Barrier(gl::UNIFORM_BARRIER_BIT);
+    /// Textures sampled in shaders
+    pub const TEXTURE_FETCH: Barrier = Barrier(gl::TEXTURE_FETCH_BARRIER_BIT);
+    /// Image load and store in shaders
+    pub const SHADER_IMAGE_ACCESS: Barrier = Barrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    /// Indirect draw and dispatch commands
+    pub const COMMAND: Barrier = Barrier(gl::COMMAND_BARRIER_BIT);
+    /// Reading textures back, e.g. `glGetTexImage`
+    pub const TEXTURE_UPDATE: Barrier = Barrier(gl::TEXTURE_UPDATE_BARRIER_BIT);
+    /// Reading or copying buffers, e.g. `glGetBufferSubData` or mapping
+    pub const BUFFER_UPDATE: Barrier = Barrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+    /// Rendering to framebuffer attachments
+    pub const FRAMEBUFFER: Barrier = Barrier(gl::FRAMEBUFFER_BARRIER_BIT);
+    /// Atomic counters
+    pub const ATOMIC_COUNTER: Barrier = Barrier(gl::ATOMIC_COUNTER_BARRIER_BIT);
+    /// Shader storage buffers
+    pub const SHADER_STORAGE: Barrier = Barrier(gl::SHADER_STORAGE_BARRIER_BIT);
+    /// Every kind of access
+    pub const ALL: Barrier = Barrier(gl::ALL_BARRIER_BITS);
+}
+
+impl std::ops::BitOr for Barrier {
+    type Output = Barrier;
+
+    fn bitor(self, other: Barrier) -> Barrier {
+        Barrier(self.0 | other.0)
+    }
+}
+
+/// Makes the writes of earlier shader invocations visible to the kinds of access in `barriers` that follow,
+/// e.g. `memory_barrier(Barrier::SHADER_IMAGE_ACCESS)` between a compute pass writing an image and one reading it
+pub fn memory_barrier(barriers: Barrier) {
+    unsafe { gl::MemoryBarrier(barriers.0) };
+}
 
-        let fragment = gl::CreateShader(gl::FRAGMENT_SHADER);
-        gl::ShaderSource(fragment, 1, &fragment_shader.as_ptr(), std::ptr::null());
-        gl::CompileShader(fragment);
-        check_compile_errors(fragment, "FRAGMENT");
+/// The GLSL source of one stage and the file it was read from
+struct StageSource {
+    stage: ShaderStage,
+    path: Option<PathBuf>,
+    code: String,
+}
+
+impl StageSource {
+    /// Reads the source of a stage from its file, if it has one
+    fn new(stage: ShaderStage, code: StageCode) -> Result<Self> {
+        match code {
+            StageCode::File(path) => Ok(StageSource { stage, code: read_file(&path)?, path: Some(path) }),
+            StageCode::Source(code) => Ok(StageSource { stage, path: None, code }),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|source| Error::AssetIo { path: path.to_path_buf(), source })
+}
 
+/// Compiles every stage and links them, the shader objects are deleted again either way
+fn compile_shader_program(stages: &[StageSource]) -> Result<GLuint> {
+    let mut shaders = Vec::with_capacity(stages.len());
+    for stage in stages {
+        match compile_stage(stage) {
+            Ok(shader) => shaders.push(shader),
+            Err(e) => {
+                delete_shaders(&shaders);
+                return Err(e);
+            }
+        }
+    }
+
+    let id;
+    unsafe {
         id = gl::CreateProgram();
-        gl::AttachShader(id, vertex);
-        gl::AttachShader(id, fragment);
+        for &shader in &shaders {
+            gl::AttachShader(id, shader);
+        }
         gl::LinkProgram(id);
-        check_compile_errors(id, "PROGRAM");
+        delete_shaders(&shaders);
 
-        gl::DeleteShader(vertex);
-        gl::DeleteShader(fragment);
+        let mut success = gl::FALSE as GLint;
+        gl::GetProgramiv(id, gl::LINK_STATUS, &mut success);
+        if success != gl::TRUE as GLint {
+            let log = info_log(id, gl::GetProgramiv, gl::GetProgramInfoLog);
+            gl::DeleteProgram(id);
+            return Err(Error::Shader(ShaderError::link(log)));
+        }
     }
 
-    id
+    Ok(id)
 }
 
-fn check_compile_errors(id: GLuint, name: &str) {
-    let mut success = gl::FALSE as GLint;
-    let mut info_log = vec![0; 1024];
+fn compile_stage(source: &StageSource) -> Result<GLuint> {
+    let compile_error = |log: String| Error::Shader(ShaderError::compile(source.stage, source.path.as_deref(), &source.code, log));
+    let code = CString::new(source.code.as_str()).map_err(|_| compile_error("source contains a null byte".to_string()))?;
+
     unsafe {
-        info_log.set_len(1024 - 1); // subtract 1 to keep a trailing null char
-
-        if name != "PROGRAM" {
-            gl::GetShaderiv(id, gl::COMPILE_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetShaderInfoLog(id, 1024, std::ptr::null_mut(), info_log.as_mut_ptr() as *mut GLchar);
-                let info_log = std::str::from_utf8(&info_log).unwrap();
-                panic!("ERROR::{name}:\n{info_log}\n");
-            }
-        } else {
-            gl::GetProgramiv(id, gl::LINK_STATUS, &mut success);
-            if success != gl::TRUE as GLint {
-                gl::GetProgramInfoLog(id, 1024, std::ptr::null_mut(), info_log.as_mut_ptr() as *mut GLchar);
-                let info_log = std::str::from_utf8(&info_log).unwrap();
-                panic!("ERROR::{name}:\n{info_log}\n");
-            };
+        let shader = gl::CreateShader(source.stage.gl_type());
+        if shader == 0 {
+            let (major, minor) = source.stage.min_gl_version();
+            return Err(compile_error(format!("the context can't create {} shaders, they need OpenGL {major}.{minor} or newer", source.stage)));
         }
+        gl::ShaderSource(shader, 1, &code.as_ptr(), std::ptr::null());
+        gl::CompileShader(shader);
+
+        let mut success = gl::FALSE as GLint;
+        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
+        if success != gl::TRUE as GLint {
+            let log = info_log(shader, gl::GetShaderiv, gl::GetShaderInfoLog);
+            gl::DeleteShader(shader);
+            return Err(compile_error(log));
+        }
+        Ok(shader)
     }
-}
\ No newline at end of file
+}
+
+fn delete_shaders(shaders: &[GLuint]) {
+    for &shader in shaders {
+        unsafe { gl::DeleteShader(shader) };
+    }
+}
+
+/// The whole info log of a shader or program, however long the driver made it
+///
+/// # Arguments
+///
+/// * `id` the shader or program
+/// * `get_parameter` `glGetShaderiv` or `glGetProgramiv`
+/// * `get_info_log` `glGetShaderInfoLog` or `glGetProgramInfoLog`
+fn info_log(
+    id: GLuint,
+    get_parameter: unsafe fn(GLuint, GLenum, *mut GLint),
+    get_info_log: unsafe fn(GLuint, GLsizei, *mut GLsizei, *mut GLchar),
+) -> String {
+    let mut length: GLint = 0;
+    unsafe { get_parameter(id, gl::INFO_LOG_LENGTH, &mut length) };
+
+    let mut log = vec![0u8; length.max(1) as usize];
+    let mut written: GLsizei = 0;
+    unsafe { get_info_log(id, log.len() as GLsizei, &mut written, log.as_mut_ptr() as *mut GLchar) };
+    log.truncate(written.clamp(0, length.max(0)) as usize);
+
+    String::from_utf8_lossy(&log).trim_end().to_string()
+}
@@ -0,0 +1,159 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use crate::shader::ShaderStage;
+
+/// Lines of source shown above the line a diagnostic points at
+const CONTEXT_LINES: usize = 1;
+
+/// One located message from a driver's info log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line in the stage's source, drivers report 0 when the message isn't about a line
+    pub line: u32,
+    /// The message starting with its severity, e.g. `error: 'foo' : undeclared identifier`
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Parses one line of an info log in the formats of the common drivers:
+    ///
+    /// * Mesa `0:12(5): error: message`
+    /// * NVIDIA `0(12) : error C1008: message`
+    /// * AMD, Intel on Windows and Apple `ERROR: 0:12: message`
+    pub fn parse(line: &str) -> Option<Diagnostic> {
+        let line = line.trim();
+        let is_number = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+
+        for severity in ["ERROR", "WARNING"] {
+            if let Some(rest) = line.strip_prefix(severity).and_then(|rest| rest.strip_prefix(':')) {
+                let (source_string, rest) = rest.split_once(':')?;
+                let (number, message) = rest.split_once(':')?;
+                if !is_number(source_string.trim()) || !is_number(number.trim()) {
+                    return None;
+                }
+                let message = format!("{}: {}", severity.to_lowercase(), message.trim());
+                return Some(Diagnostic { line: number.trim().parse().ok()?, message });
+            }
+        }
+
+        let separator = line.find([':', '('])?;
+        let (source_string, rest) = (&line[..separator], &line[separator + 1..]);
+        if !is_number(source_string) {
+            return None;
+        }
+        let (number, message) = if line[separator..].starts_with(':') {
+            let (number, rest) = rest.split_once('(')?;
+            let (column, message) = rest.split_once("):")?;
+            if !is_number(column) {
+                return None;
+            }
+            (number, message)
+        } else {
+            let (number, rest) = rest.split_once(')')?;
+            (number, rest.trim_start().strip_prefix(':')?)
+        };
+        if !is_number(number) {
+            return None;
+        }
+        Some(Diagnostic { line: number.parse().ok()?, message: message.trim().to_string() })
+    }
+
+    /// The located messages of a whole info log, lines in other formats are skipped
+    pub fn parse_log(log: &str) -> Vec<Diagnostic> {
+        log.lines().filter_map(Diagnostic::parse).collect()
+    }
+}
+
+/// A shader stage that failed to compile or a program that failed to link, with the driver's info log
+#[derive(Debug)]
+pub struct ShaderError {
+    /// The stage that failed to compile, `None` when linking failed
+    pub stage: Option<ShaderStage>,
+    /// The file the stage was read from, `None` for source held in memory and for link failures
+    pub path: Option<PathBuf>,
+    /// The stage's source, used to show the lines the diagnostics point at
+    pub source: Option<String>,
+    /// The complete info log
+    pub log: String,
+    /// The located messages found in `log`
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ShaderError {
+    /// A stage that failed to compile
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` the stage that failed
+    /// * `path` the file the source was read from, if any
+    /// * `source` the source that was compiled
+    /// * `log` the driver's info log
+    pub fn compile(stage: ShaderStage, path: Option<&Path>, source: &str, log: String) -> Self {
+        ShaderError {
+            stage: Some(stage),
+            path: path.map(Path::to_path_buf),
+            source: Some(source.to_string()),
+            diagnostics: Diagnostic::parse_log(&log),
+            log,
+        }
+    }
+
+    /// A program that failed to link
+    ///
+    /// # Arguments
+    ///
+    /// * `log` the driver's info log
+    pub fn link(log: String) -> Self {
+        ShaderError { stage: None, path: None, source: None, diagnostics: Diagnostic::parse_log(&log), log }
+    }
+
+    /// The lines of the source up to `line`, numbered in a gutter of `width` digits
+    fn excerpt(&self, line: usize, width: usize) -> Vec<String> {
+        let Some(source) = &self.source else {
+            return Vec::new();
+        };
+        let first = line.saturating_sub(CONTEXT_LINES).max(1);
+        let lines: Vec<(usize, &str)> = (1..).zip(source.lines()).skip(first - 1).take(line + 1 - first).collect();
+        if lines.last().map(|(number, _)| *number) != Some(line) {
+            return Vec::new();
+        }
+
+        std::iter::once(format!("{:width$} |", ""))
+            .chain(lines.into_iter().map(|(number, text)| format!("{number:>width$} | {}", text.trim_end())))
+            .collect()
+    }
+}
+
+impl Display for ShaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let location = self.path.as_ref().map(|path| path.display().to_string());
+        match (&self.stage, &location) {
+            (Some(stage), Some(path)) => write!(f, "failed to compile {stage} shader {path}:")?,
+            (Some(stage), None) => write!(f, "failed to compile {stage} shader:")?,
+            (None, _) => write!(f, "failed to link shader program:")?,
+        }
+
+        // logs in a format that isn't recognized are shown as they are
+        if self.diagnostics.is_empty() {
+            return write!(f, "\n{}", self.log);
+        }
+
+        let width = self.diagnostics.iter().map(|diagnostic| diagnostic.line.to_string().len()).max().unwrap_or(1);
+        for (index, diagnostic) in self.diagnostics.iter().enumerate() {
+            // a blank line between diagnostics, like a compiler separates its errors
+            let separator = if index > 0 { "\n" } else { "" };
+            write!(f, "\n{separator}{}", diagnostic.message)?;
+            if diagnostic.line == 0 {
+                continue;
+            }
+            let path = location.as_deref().unwrap_or("<source>");
+            write!(f, "\n{:width$}--> {path}:{}", "", diagnostic.line)?;
+            for line in self.excerpt(diagnostic.line as usize, width) {
+                write!(f, "\n{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShaderError {}
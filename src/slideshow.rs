@@ -0,0 +1,215 @@
+use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use glfw::{Action, Key};
+use crate::app::{App, Context};
+use crate::error::{Error, Result};
+use crate::lister::{Lister, RunID};
+
+/// How one exercise fared in a slideshow
+#[derive(Clone, Debug)]
+pub enum Outcome {
+    /// Ran until it was advanced without any problems
+    Passed,
+    /// Setup failed because a shader did not compile or link
+    ShaderFailed(String),
+    /// Setup returned any other error, e.g. a missing asset
+    Failed(String),
+    /// Panicked in one of its hooks
+    Crashed(String),
+    /// The slideshow was closed before reaching it
+    Skipped,
+}
+
+impl Outcome {
+    /// Whether the outcome counts against the exercise
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::ShaderFailed(_) | Outcome::Failed(_) | Outcome::Crashed(_))
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "ok"),
+            Outcome::ShaderFailed(reason) => write!(f, "shader failed: {reason}"),
+            Outcome::Failed(reason) => write!(f, "failed: {reason}"),
+            Outcome::Crashed(reason) => write!(f, "crashed: {reason}"),
+            Outcome::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Runs every exercise and variant one after the other in a single window.
+///
+/// Each exercise is shown for `seconds_per_exercise` on the context's clock, or until Space, Enter, Right or
+/// Page Down advances it. Panics and setup errors are caught and recorded so one broken exercise doesn't stop
+/// the rest, `summary` reports them afterwards.
+pub struct Slideshow {
+    lister: Lister,
+    entries: Vec<RunID>,
+    /// `Lister::label` of each entry
+    labels: Vec<String>,
+    outcomes: Vec<Outcome>,
+    /// Index of the entry on screen, `entries.len()` once all have been shown
+    current: usize,
+    running: Option<Box<dyn App>>,
+    /// Context time at which the current exercise started
+    started_at: f64,
+    seconds_per_exercise: Option<f64>,
+}
+
+impl Slideshow {
+    /// Creates a slideshow of every run and variant in `lister`
+    ///
+    /// # Arguments
+    ///
+    /// * `lister` the runs to show, in its order
+    /// * `seconds_per_exercise` how long to show each one, `None` to wait for a key press
+    pub fn new(lister: Lister, seconds_per_exercise: Option<f64>) -> Self {
+        let entries = lister.ids();
+        let labels = entries.iter().map(|id| lister.label(id).expect("ids only holds listed runs")).collect();
+        let outcomes = vec![Outcome::Skipped; entries.len()];
+
+        Slideshow { lister, entries, labels, outcomes, current: 0, running: None, started_at: 0.0, seconds_per_exercise }
+    }
+
+    /// Each exercise with how it fared
+    pub fn results(&self) -> impl Iterator<Item = (&str, &Outcome)> {
+        self.labels.iter().map(String::as_str).zip(&self.outcomes)
+    }
+
+    /// Whether any exercise failed or crashed
+    pub fn has_failures(&self) -> bool {
+        self.outcomes.iter().any(Outcome::is_failure)
+    }
+
+    /// One line per exercise with its outcome, followed by the totals
+    pub fn summary(&self) -> String {
+        let width = self.labels.iter().map(String::len).max().unwrap_or(0);
+        // shader logs span several lines, keep one row per exercise
+        let mut lines: Vec<String> = self.results()
+            .map(|(label, outcome)| format!("{label:width$}  {}", outcome.to_string().lines().collect::<Vec<&str>>().join(" ")))
+            .collect();
+
+        let passed = self.outcomes.iter().filter(|outcome| matches!(outcome, Outcome::Passed)).count();
+        let failed = self.outcomes.iter().filter(|outcome| outcome.is_failure()).count();
+        let skipped = self.outcomes.len() - passed - failed;
+        lines.push(format!("{passed} passed, {failed} failed, {skipped} skipped"));
+        lines.join("\n")
+    }
+
+    /// Starts the entry at `index`, moving on to the next one right away if it fails
+    fn start(&mut self, ctx: &mut Context, index: usize) {
+        self.current = index;
+        let Some(id) = self.entries.get(index) else {
+            // nothing left to show
+            ctx.set_should_close(true);
+            return;
+        };
+
+        let label = &self.labels[index];
+        println!("{label}");
+        ctx.set_title(label);
+        self.started_at = ctx.time();
+
+        let mut app = match self.lister.create_app(id, ctx.param_overrides()) {
+            Ok(app) => app,
+            Err(e) => {
+                self.fail(ctx, None, Outcome::Failed(e.to_string()));
+                return;
+            }
+        };
+        match catch_crash(|| app.setup(ctx)) {
+            Ok(Ok(_)) => {
+                self.outcomes[index] = Outcome::Passed;
+                self.running = Some(app);
+            }
+            Ok(Err(e @ Error::Shader(_))) => self.fail(ctx, Some(app), Outcome::ShaderFailed(e.to_string())),
+            Ok(Err(e)) => self.fail(ctx, Some(app), Outcome::Failed(e.to_string())),
+            Err(reason) => self.fail(ctx, Some(app), Outcome::Crashed(reason)),
+        }
+    }
+
+    /// Records why the current entry failed, cleans up after it and starts the next one
+    fn fail(&mut self, ctx: &mut Context, app: Option<Box<dyn App>>, outcome: Outcome) {
+        eprintln!("{}: {outcome}", self.labels[self.current]);
+        self.outcomes[self.current] = outcome;
+        if let Some(mut app) = app {
+            // a half set up exercise may well panic again while tearing down, there is nothing more to learn from that
+            let _ = catch_crash(|| app.teardown(ctx));
+        }
+        self.running = None;
+        ctx.reset_gl_state();
+        self.start(ctx, self.current + 1);
+    }
+
+    /// Tears down the current entry and starts the next one
+    fn advance(&mut self, ctx: &mut Context) {
+        if let Some(mut app) = self.running.take() {
+            if let Err(reason) = catch_crash(|| app.teardown(ctx)) {
+                self.outcomes[self.current] = Outcome::Crashed(reason);
+            }
+            ctx.reset_gl_state();
+        }
+        self.start(ctx, self.current + 1);
+    }
+
+    /// Runs a hook of the current entry, treating a panic as a crash
+    fn guard(&mut self, ctx: &mut Context, hook: impl FnOnce(&mut dyn App, &mut Context)) {
+        let Some(app) = &mut self.running else { return };
+        if let Err(reason) = catch_crash(|| hook(app.as_mut(), ctx)) {
+            let app = self.running.take();
+            self.fail(ctx, app, Outcome::Crashed(reason));
+        }
+    }
+}
+
+impl App for Slideshow {
+    fn setup(&mut self, ctx: &mut Context) -> Result<()> {
+        self.start(ctx, 0);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut Context, delta_time: f32) {
+        let timed_out = self.seconds_per_exercise.is_some_and(|seconds| ctx.time() - self.started_at >= seconds);
+        if timed_out {
+            self.advance(ctx);
+        }
+        self.guard(ctx, |app, ctx| app.update(ctx, delta_time));
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        if self.running.is_none() {
+            // the last exercise failed and the loop is about to stop
+            unsafe {
+                gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+                gl::Clear(gl::COLOR_BUFFER_BIT);
+            }
+        }
+        self.guard(ctx, |app, ctx| app.render(ctx));
+    }
+
+    fn on_event(&mut self, ctx: &mut Context, event: &glfw::WindowEvent) {
+        match *event {
+            glfw::WindowEvent::Key(Key::Space | Key::Enter | Key::Right | Key::PageDown, _, Action::Press, _) => self.advance(ctx),
+            _ => self.guard(ctx, |app, ctx| app.on_event(ctx, event)),
+        }
+    }
+
+    fn teardown(&mut self, ctx: &mut Context) {
+        if let Some(mut app) = self.running.take() {
+            if let Err(reason) = catch_crash(|| app.teardown(ctx)) {
+                self.outcomes[self.current] = Outcome::Crashed(reason);
+            }
+        }
+    }
+}
+
+/// Calls `f`, turning a panic into its message
+fn catch_crash<T>(f: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_string())
+    })
+}
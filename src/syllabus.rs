@@ -0,0 +1,127 @@
+use crate::lister::Lister;
+
+/// Marks the start of the generated checklist in the readme
+pub const CHECKLIST_BEGIN: &str = "<!-- checklist: generated by `learn_opengl status --update-readme` -->";
+/// Marks the end of the generated checklist in the readme
+pub const CHECKLIST_END: &str = "<!-- checklist end -->";
+
+/// A chapter of learnopengl.com, exercises use its position as CHAPTER and the position of the page as SECTION
+pub struct Chapter {
+    pub title: &'static str,
+    pub sections: &'static [&'static str],
+}
+
+/// Every chapter and page of learnopengl.com in the order of the site's menu
+pub const CHAPTERS: &[Chapter] = &[
+    Chapter {
+        title: "Getting Started",
+        sections: &["Creating a window", "Hello Window", "Hello Triangle", "Shaders", "Textures", "Transformations", "Coordinate Systems", "Camera", "Review"],
+    },
+    Chapter {
+        title: "Lighting",
+        sections: &["Colors", "Basic Lighting", "Materials", "Lighting Maps", "Light Casters", "Multiple Lights", "Review"],
+    },
+    Chapter {
+        title: "Model Loading",
+        sections: &["Assimp", "Mesh", "Model"],
+    },
+    Chapter {
+        title: "Advanced OpenGL",
+        sections: &["Depth Testing", "Stencil Testing", "Blending", "Face Culling", "Framebuffers", "Cubemaps", "Advanced Data", "Advanced GLSL", "Geometry Shader", "Instancing", "Anti Aliasing"],
+    },
+    Chapter {
+        title: "Advanced Lighting",
+        sections: &["Advanced Lighting", "Gamma Correction", "Shadows", "Normal Mapping", "Parallax Mapping", "HDR", "Bloom", "Deferred Shading", "SSAO"],
+    },
+    Chapter {
+        title: "PBR",
+        sections: &["Theory", "Lighting", "IBL"],
+    },
+    Chapter {
+        title: "In Practice",
+        sections: &["Debugging", "Text Rendering", "2D Game"],
+    },
+    Chapter {
+        title: "Guest Articles",
+        sections: &["How to publish", "OIT", "Skeletal Animation", "CSM", "Scene", "Tessellation", "DSA", "Compute Shaders", "Phys Based Bloom", "Area Lights"],
+    },
+];
+
+/// Iterates the chapters with their CHAPTER number
+fn numbered_chapters() -> impl Iterator<Item = (i32, &'static Chapter)> {
+    (1..).zip(CHAPTERS)
+}
+
+/// Iterates the sections of a chapter with their SECTION number
+fn numbered_sections(chapter: &Chapter) -> impl Iterator<Item = (i32, &'static str)> {
+    (1..).zip(chapter.sections.iter().copied())
+}
+
+/// Names of the registered exercises for a page
+fn implementations(lister: &Lister, chapter: i32, section: i32) -> Vec<&'static str> {
+    lister.runners().filter(|runner| (runner.chapter(), runner.section()) == (chapter, section)).map(|runner| runner.name()).collect()
+}
+
+/// Whether a page has an exercise
+fn is_done(lister: &Lister, chapter: i32, section: i32) -> bool {
+    !implementations(lister, chapter, section).is_empty()
+}
+
+/// Counts per chapter and overall, then every page with its exercises. Exercises outside the syllabus are listed at the end
+pub fn report(lister: &Lister) -> String {
+    let mut lines = Vec::new();
+    let mut done_total = 0;
+    let mut section_total = 0;
+    let width = CHAPTERS.iter().flat_map(|chapter| chapter.sections).map(|title| title.len()).max().unwrap_or(0);
+
+    for (chapter_number, chapter) in numbered_chapters() {
+        let done = numbered_sections(chapter).filter(|(section, _)| is_done(lister, chapter_number, *section)).count();
+        done_total += done;
+        section_total += chapter.sections.len();
+        lines.push(format!("{chapter_number} {} ({done}/{})", chapter.title, chapter.sections.len()));
+
+        for (section_number, title) in numbered_sections(chapter) {
+            let names = implementations(lister, chapter_number, section_number);
+            let mark = if names.is_empty() { " " } else { "x" };
+            let id = format!("{chapter_number}.{section_number}");
+            let line = format!("  [{mark}] {id:5} {title:width$}  {}", names.join(", "));
+            lines.push(line.trim_end().to_string());
+        }
+    }
+    lines.push(format!("{done_total}/{section_total} pages done"));
+
+    let unknown: Vec<String> = lister.runners()
+        .filter(|runner| section_title(runner.chapter(), runner.section()).is_none())
+        .map(|runner| format!("  {}.{} {}", runner.chapter(), runner.section(), runner.name()))
+        .collect();
+    if !unknown.is_empty() {
+        lines.push("not part of the syllabus:".to_string());
+        lines.extend(unknown);
+    }
+
+    lines.join("\n")
+}
+
+/// The title of the page at CHAPTER.SECTION
+pub fn section_title(chapter: i32, section: i32) -> Option<&'static str> {
+    let chapter = CHAPTERS.get(usize::try_from(chapter).ok()?.checked_sub(1)?)?;
+    chapter.sections.get(usize::try_from(section).ok()?.checked_sub(1)?).copied()
+}
+
+/// The markdown checklist of the readme, one heading per chapter and a ticked box for every page with an exercise
+pub fn checklist(lister: &Lister) -> String {
+    numbered_chapters().map(|(chapter_number, chapter)| {
+        let items: Vec<String> = numbered_sections(chapter).map(|(section_number, title)| {
+            let mark = if is_done(lister, chapter_number, section_number) { "x" } else { " " };
+            format!("* [{mark}] {title}")
+        }).collect();
+        format!("### {}\n\n{}\n", chapter.title, items.join("\n"))
+    }).collect::<Vec<String>>().join("\n")
+}
+
+/// Replaces the checklist between the markers in the readme, `None` when the markers are missing
+pub fn update_readme(readme: &str, lister: &Lister) -> Option<String> {
+    let (before, rest) = readme.split_once(CHECKLIST_BEGIN)?;
+    let (_, after) = rest.split_once(CHECKLIST_END)?;
+    Some(format!("{before}{CHECKLIST_BEGIN}\n\n{}\n{CHECKLIST_END}{after}", checklist(lister)))
+}
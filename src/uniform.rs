@@ -0,0 +1,74 @@
+use cgmath::{Matrix2, Matrix3, Matrix4, Point3, Vector2, Vector3, Vector4};
+use gl::types::{GLint, GLsizei};
+
+/// A value `Shader::set` can upload to a uniform of the matching GLSL type, arrays and slices of a type upload to
+/// a uniform array of it
+pub trait UniformValue {
+    /// Uploads the value to the uniform at `location` of the program in use
+    fn upload(&self, location: GLint);
+}
+
+/// The texture unit a `sampler2D` or other sampler uniform reads from, e.g. `Sampler(1)` for `gl::TEXTURE1`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Sampler(pub GLint);
+
+/// Implements `UniformValue` for a type, slices of it and arrays of it. `$upload` uploads the slice `$values`
+/// to `$location`, the single value and arrays go through it as slices
+macro_rules! uniform_value {
+    ($type:ty, |$values:ident, $location:ident| $upload:expr) => {
+        impl UniformValue for $type {
+            fn upload(&self, location: GLint) {
+                std::slice::from_ref(self).upload(location)
+            }
+        }
+
+        impl UniformValue for [$type] {
+            fn upload(&self, location: GLint) {
+                let ($values, $location) = (self, location);
+                unsafe { $upload }
+            }
+        }
+
+        impl<const N: usize> UniformValue for [$type; N] {
+            fn upload(&self, location: GLint) {
+                self.as_slice().upload(location)
+            }
+        }
+    };
+}
+
+uniform_value!(f32, |values, location| gl::Uniform1fv(location, count(values), values.as_ptr()));
+uniform_value!(i32, |values, location| gl::Uniform1iv(location, count(values), values.as_ptr()));
+uniform_value!(u32, |values, location| gl::Uniform1uiv(location, count(values), values.as_ptr()));
+uniform_value!(bool, |values, location| {
+    let values: Vec<GLint> = values.iter().map(|&value| value as GLint).collect();
+    gl::Uniform1iv(location, count(&values), values.as_ptr())
+});
+uniform_value!(Sampler, |values, location| gl::Uniform1iv(location, count(values), values.as_ptr() as *const GLint));
+
+// the cgmath types are `repr(C)` structs of their components, matrices stored by column as OpenGL expects
+uniform_value!(Vector2<f32>, |values, location| gl::Uniform2fv(location, count(values), values.as_ptr() as *const f32));
+uniform_value!(Vector3<f32>, |values, location| gl::Uniform3fv(location, count(values), values.as_ptr() as *const f32));
+uniform_value!(Vector4<f32>, |values, location| gl::Uniform4fv(location, count(values), values.as_ptr() as *const f32));
+uniform_value!(Point3<f32>, |values, location| gl::Uniform3fv(location, count(values), values.as_ptr() as *const f32));
+uniform_value!(Matrix2<f32>, |values, location| gl::UniformMatrix2fv(location, count(values), gl::FALSE, values.as_ptr() as *const f32));
+uniform_value!(Matrix3<f32>, |values, location| gl::UniformMatrix3fv(location, count(values), gl::FALSE, values.as_ptr() as *const f32));
+uniform_value!(Matrix4<f32>, |values, location| gl::UniformMatrix4fv(location, count(values), gl::FALSE, values.as_ptr() as *const f32));
+
+impl<T: UniformValue + ?Sized> UniformValue for &T {
+    fn upload(&self, location: GLint) {
+        (**self).upload(location)
+    }
+}
+
+impl<T> UniformValue for Vec<T> where [T]: UniformValue {
+    fn upload(&self, location: GLint) {
+        self.as_slice().upload(location)
+    }
+}
+
+/// The number of array elements to upload
+fn count<T>(values: &[T]) -> GLsizei {
+    values.len() as GLsizei
+}
@@ -0,0 +1,38 @@
+use std::process::{Command, ExitStatus};
+use learn_opengl::app::{parse_seconds, Settings};
+use learn_opengl::golden;
+
+/// Runs the binary with `args` and returns how it exited
+fn run(args: &[&str]) -> ExitStatus {
+    Command::new(env!("CARGO_BIN_EXE_learn_opengl")).args(args).output().unwrap().status
+}
+
+#[test]
+fn seconds_must_be_finite_and_positive() {
+    assert_eq!(parse_seconds("0.5"), Ok(0.5));
+    assert_eq!(parse_seconds("2"), Ok(2.0));
+    for value in ["0", "-1", "NaN", "inf", "", "soon"] {
+        assert!(parse_seconds(value).is_err(), "{value} was accepted");
+    }
+}
+
+#[test]
+fn budgets_that_never_end_are_rejected_before_running() {
+    // clap exits with 2 for invalid arguments
+    for args in [["--duration", "NaN"], ["--duration=-1", "--headless"], ["--time-step", "0"], ["--time-step", "inf"]] {
+        let status = run(&["1.3", args[0], args[1]]);
+        assert_eq!(status.code(), Some(2), "{args:?}");
+    }
+}
+
+#[test]
+fn runs_end_with_their_budget() {
+    if golden::headless_or_skip(&Settings::default()).is_none() {
+        return;
+    }
+
+    assert!(run(&["1.3", "--headless", "--frames", "3"]).success());
+    // 5 frames of 0.1 seconds on the fixed clock
+    assert!(run(&["1.3", "--headless", "--duration", "0.5", "--time-step", "0.1"]).success());
+    assert_eq!(run(&["9.9", "--headless", "--frames", "1"]).code(), Some(1));
+}
@@ -0,0 +1,79 @@
+use learn_opengl::app::{self, App, Backend, Context, Settings};
+use learn_opengl::clock::{Clock, FixedClock};
+use learn_opengl::error::Result;
+use learn_opengl::golden;
+
+#[test]
+fn fixed_clock_advances_one_step_per_tick() {
+    let mut clock = FixedClock::new(0.25);
+    assert_eq!(clock.now(), 0.0);
+    let times: Vec<f64> = (0..4).map(|_| {
+        clock.tick();
+        clock.now()
+    }).collect();
+    assert_eq!(times, [0.25, 0.5, 0.75, 1.0]);
+}
+
+#[test]
+fn fixed_clocks_replay_identically() {
+    // 1/60 isn't exact in binary, two clocks still have to agree bit for bit
+    let (mut first, mut second) = (FixedClock::new(golden::TIME_STEP), FixedClock::new(golden::TIME_STEP));
+    for _ in 0..1000 {
+        first.tick();
+        second.tick();
+        assert_eq!(first.now().to_bits(), second.now().to_bits());
+    }
+}
+
+#[test]
+fn manual_clock_only_moves_by_hand() {
+    let mut clock = FixedClock::manual();
+    clock.tick();
+    assert_eq!(clock.now(), 0.0);
+
+    clock.advance(1.5);
+    clock.tick();
+    assert_eq!(clock.now(), 1.5);
+
+    clock.set(0.5);
+    assert_eq!(clock.now(), 0.5);
+
+    let mut stepping = FixedClock::new(1.0);
+    stepping.advance(0.5);
+    stepping.tick();
+    assert_eq!(stepping.now(), 1.5);
+}
+
+/// Records the time each hook sees
+#[derive(Default)]
+struct Recorder {
+    delta_times: Vec<f32>,
+    render_times: Vec<f64>,
+}
+
+impl App for Recorder {
+    fn setup(&mut self, _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, _ctx: &mut Context, delta_time: f32) {
+        self.delta_times.push(delta_time);
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        self.render_times.push(ctx.time());
+    }
+}
+
+#[test]
+fn render_loop_sees_one_step_per_frame() {
+    let settings = Settings { backend: Backend::Headless, fixed_time_step: Some(0.5), ..Settings::default() };
+    if golden::headless_or_skip(&settings).is_none() {
+        return;
+    }
+    let mut recorder = Recorder::default();
+    app::render_frames("clock", &mut recorder, &settings, 4).unwrap();
+
+    assert_eq!(recorder.render_times, [0.0, 0.5, 1.0, 1.5]);
+    assert!(recorder.delta_times.iter().skip(1).all(|&delta_time| delta_time == 0.5), "{:?}", recorder.delta_times);
+}
@@ -0,0 +1,48 @@
+use learn_opengl::app::{self, Backend, Settings, SCR_HEIGHT};
+use learn_opengl::compare::{Compare, CompareMode};
+use learn_opengl::golden::{self, Tolerance};
+use learn_opengl::error::Error;
+use learn_opengl::lister::{Lister, RunID};
+
+fn headless_settings() -> Option<Settings> {
+    let settings = Settings { backend: Backend::Headless, fixed_time_step: Some(golden::TIME_STEP), ..Settings::default() };
+    golden::headless_or_skip(&settings).map(|_| settings)
+}
+
+/// An exercise compared with itself in wipe mode must look like the exercise on its own, apart from the divider.
+/// 1.8 enables depth testing in `setup`, which only survives the switches between the two sides if it is restored.
+/// 1.5 sets a uniform every frame, which only reaches its own program if it activates the program first
+#[test]
+fn wipe_of_an_exercise_with_itself_matches_the_exercise() {
+    let Some(settings) = headless_settings() else {
+        return;
+    };
+    let lister = Lister::new();
+    // the divider covers two columns of the window
+    let tolerance = Tolerance { per_channel: 8, max_mismatched_pixels: 2 * SCR_HEIGHT as usize };
+
+    for id in ["1.3", "1.5", "1.8"] {
+        let id: RunID = id.parse().unwrap();
+        let mut compare = Compare::new(&lister, &id, &id, CompareMode::Wipe, &[]).unwrap();
+        let actual = app::render_frames(&compare.title(), &mut compare, &settings, golden::FRAMES).unwrap();
+        let expected = golden::render(lister.find(&id).unwrap(), None, golden::FRAMES).unwrap();
+
+        let comparison = golden::compare(&actual, &expected, &tolerance).unwrap();
+        assert!(comparison.passed(&tolerance), "{id}: {} pixels differ", comparison.mismatched_pixels);
+    }
+}
+
+#[test]
+fn failed_setups_return_the_error() {
+    let Some(mut settings) = headless_settings() else {
+        return;
+    };
+    settings.params = vec![("ratio".to_string(), "2".to_string())];
+    let lister = Lister::new();
+    let id: RunID = "1.5".parse().unwrap();
+    let mut compare = Compare::new(&lister, &id, &id, CompareMode::SideBySide, &settings.params).unwrap();
+    match app::render_frames(&compare.title(), &mut compare, &settings, 1) {
+        Err(Error::InvalidParam { name, .. }) => assert_eq!(name, "ratio"),
+        other => panic!("expected the out of range ratio to fail, got {:?}", other.map(|_| ()).map_err(|e| e.to_string())),
+    }
+}
@@ -0,0 +1,20 @@
+use std::process::Command;
+
+/// The generated script for `shell`
+fn completions(shell: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_learn_opengl")).args(["completions", shell]).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn compare_completes_exercise_ids() {
+    let bash = completions("bash");
+    let (_, compare) = bash.split_once("learn_opengl__subcmd__compare)").expect("bash script has a compare case");
+    let opts = compare.lines().find(|line| line.trim_start().starts_with("opts=")).unwrap();
+    assert!(opts.contains(" 1.3.2 ") && opts.contains(" hello-triangle "), "{opts}");
+
+    let fish = completions("fish");
+    assert!(fish.contains("-n \"__fish_seen_subcommand_from compare\" -f -a '1.8'"));
+    assert!(fish.contains("-n \"__fish_use_subcommand\" -f -a '1.3.2'"));
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use glfw::Key;
+use learn_opengl::app::DisplayMode;
+use learn_opengl::config::{self, Config};
+use learn_opengl::error::Error;
+use learn_opengl::lister::RunID;
+
+/// Writes `contents` to a file in a directory of its own under the system temp directory
+fn write_config(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("learn_opengl_config_{}_{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(config::FILE_NAME);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn later_files_override_earlier_ones_key_by_key() {
+    let user = write_config("user", "default_exercise = \"textures\"\n[window]\nwidth = 1024\nheight = 768\n[camera]\nspeed = 4.0\n");
+    let project = write_config("project", "[window]\nwidth = 640\ndisplay_mode = \"borderless\"\n[bindings]\nforward = \"Up\"\n");
+
+    let config = Config::load_files([&user, &project]).unwrap();
+    assert_eq!(config.default_exercise, Some(RunID::Named("textures".to_string())));
+
+    let settings = config.settings();
+    assert_eq!((settings.window.width, settings.window.height), (640, 768));
+    assert_eq!(settings.window.display_mode, DisplayMode::Borderless);
+    assert_eq!(settings.camera.movement_speed, 4.0);
+    assert_eq!(settings.bindings.forward, Key::Up);
+    assert_eq!(settings.bindings.backward, Key::S);
+}
+
+#[test]
+fn missing_files_are_skipped() {
+    let missing = std::env::temp_dir().join("learn_opengl_config_missing").join(config::FILE_NAME);
+    let config = Config::load_files([&missing]).unwrap();
+    assert_eq!(config.settings().window.width, 800);
+    assert!(matches!(Config::load(Some(&missing)), Err(Error::Config { .. })));
+}
+
+#[test]
+fn the_asset_root_is_relative_to_its_file() {
+    let path = write_config("assets", "asset_root = \"assets\"\n");
+    let config = Config::load_files([&path]).unwrap();
+    assert_eq!(config.settings().asset_root, path.parent().unwrap().join("assets"));
+}
+
+#[test]
+fn mistakes_name_the_file() {
+    for contents in ["[window]\nwidht = 640\n", "[bindings]\nquit = \"Hyper\"\n", "[window]\ngl_version = \"four\"\n", "default_exercise = \"1.3.0\"\n"] {
+        let path = write_config("invalid", contents);
+        match Config::load_files([&path]) {
+            Err(Error::Config { path: invalid, .. }) => assert_eq!(invalid, path),
+            other => panic!("expected {contents:?} to be rejected, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn key_names_ignore_case_and_separators() {
+    assert_eq!(config::parse_key("w"), Some(Key::W));
+    assert_eq!(config::parse_key("Page Up"), Some(Key::PageUp));
+    assert_eq!(config::parse_key("left_shift"), Some(Key::LeftShift));
+    assert_eq!(config::parse_key("F12"), Some(Key::F12));
+    assert_eq!(config::parse_key("Hyper"), None);
+}
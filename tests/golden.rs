@@ -0,0 +1,58 @@
+use std::path::Path;
+use learn_opengl::app::{Backend, Context, Settings, SCR_HEIGHT, SCR_WIDTH};
+use learn_opengl::golden::{self, Tolerance};
+use learn_opengl::lister::Lister;
+
+/// Renders every registered exercise and variant offscreen and compares it with `tests/golden/<ch>.<s>[.<variant>].<name>.png`.
+///
+/// Run with `UPDATE_GOLDEN=1` to regenerate the references after an intended visual change.
+#[test]
+fn exercises_match_reference_images() {
+    if golden::headless_or_skip(&Settings::default()).is_none() {
+        return;
+    }
+
+    let reference_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+    // leaves room for rasterization differences between Mesa versions along triangle edges
+    let tolerance = Tolerance { per_channel: 8, max_mismatched_pixels: 200 };
+
+    let failures: Vec<String> = Lister::new()
+        .runners()
+        .flat_map(|runner| {
+            let variants = (1..=runner.variants().len() as u32).map(Some);
+            std::iter::once(None).chain(variants).map(move |variant| (runner, variant))
+        })
+        .filter_map(|(runner, variant)| {
+            let name = golden::reference_name(runner, variant);
+            match golden::render(runner, variant, golden::FRAMES) {
+                Ok(image) => golden::check(&name, &image, &reference_dir, &output_dir, &tolerance).err(),
+                Err(e) => Some(format!("{name}: {e}")),
+            }
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "golden image mismatches:\n{}", failures.join("\n"));
+}
+
+/// Test files run their tests on parallel threads, each with its own context on the shared EGL display.
+/// Dropping one context must leave the others usable
+#[test]
+fn contexts_on_parallel_threads_stay_usable() {
+    if golden::headless_or_skip(&Settings::default()).is_none() {
+        return;
+    }
+
+    let threads: Vec<_> = (0..8).map(|_| std::thread::spawn(|| {
+        (0..10).map(|_| Context::new("parallel", &Settings { backend: Backend::Headless, ..Settings::default() })
+            .map(|ctx| ctx.capture().dimensions())
+            .map_err(|e| e.to_string()))
+            .collect::<Vec<_>>()
+    })).collect();
+
+    for thread in threads {
+        for result in thread.join().unwrap() {
+            assert_eq!(result, Ok((SCR_WIDTH, SCR_HEIGHT)));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use learn_opengl::error::Error;
+use learn_opengl::lister::{Lister, RunID};
+
+fn find_name(name: &str) -> Result<&'static str, Error> {
+    Lister::new().find(&RunID::Named(name.to_string())).map(|runner| runner.name())
+}
+
+#[test]
+fn names_match_ignoring_case_and_separators() {
+    assert_eq!(find_name("hello triangle").unwrap(), "hello triangle");
+    assert_eq!(find_name("Hello-Triangle").unwrap(), "hello triangle");
+    assert_eq!(find_name("creating_a_window").unwrap(), "creating a window");
+}
+
+#[test]
+fn unique_prefixes_match() {
+    assert_eq!(find_name("cam").unwrap(), "camera");
+    assert_eq!(find_name("hello-t").unwrap(), "hello triangle");
+}
+
+#[test]
+fn shared_prefixes_are_ambiguous() {
+    match find_name("hello") {
+        Err(Error::AmbiguousId { matches, .. }) => assert_eq!(matches, ["hello window", "hello triangle"]),
+        other => panic!("expected an ambiguous match, got {:?}", other.map_err(|e| e.to_string())),
+    }
+}
+
+#[test]
+fn misspelled_names_get_suggestions() {
+    match find_name("textrues") {
+        Err(Error::UnknownId { suggestions, .. }) => assert_eq!(suggestions, ["textures"]),
+        other => panic!("expected suggestions, got {:?}", other.map_err(|e| e.to_string())),
+    }
+    match find_name("not an exercise") {
+        Err(Error::UnknownId { suggestions, .. }) => assert!(suggestions.is_empty()),
+        other => panic!("expected no match, got {:?}", other.map_err(|e| e.to_string())),
+    }
+}
+
+#[test]
+fn run_ids_parse_with_optional_variant() {
+    assert_eq!("1.3".parse::<RunID>().unwrap(), RunID::Numeric { chapter: 1, section: 3, variant: None });
+    assert_eq!("1.3.2".parse::<RunID>().unwrap(), RunID::Numeric { chapter: 1, section: 3, variant: Some(2) });
+    assert_eq!("hello triangle".parse::<RunID>().unwrap(), RunID::Named("hello triangle".to_string()));
+
+    for invalid in ["", "1", "1.x", "1.8.2.3", "1.3.0"] {
+        assert!(matches!(invalid.parse::<RunID>(), Err(Error::InvalidId { .. })), "{invalid:?} should not parse");
+    }
+}
+
+#[test]
+fn variants_must_exist() {
+    let lister = Lister::new();
+    assert!(lister.find(&"1.3.3".parse().unwrap()).is_ok());
+    assert!(matches!(lister.find(&"1.3.4".parse().unwrap()), Err(Error::UnknownId { .. })));
+    assert!(matches!(lister.find(&"1.5.1".parse().unwrap()), Err(Error::UnknownId { .. })));
+}
+
+#[test]
+fn registered_runners_are_unique() {
+    let lister = Lister::new();
+    assert!(lister.runners().count() >= 6, "registered runners went missing");
+
+    let duplicates = lister.duplicates();
+    assert!(duplicates.is_empty(), "duplicate registrations:\n{}", duplicates.join("\n"));
+}
+
+#[test]
+fn completion_hints_resolve_to_their_runner() {
+    let lister = Lister::new();
+    let hints = lister.completion_hints();
+    assert!(hints.iter().any(|(value, _)| value == "hello-triangle"));
+    assert!(hints.iter().any(|(value, _)| value == "1.3.2"));
+    for (value, _) in hints {
+        assert!(!value.contains(' '), "{value} needs quoting");
+        assert!(lister.find(&value.parse().unwrap()).is_ok(), "{value} doesn't resolve");
+    }
+}
+
+#[test]
+fn apps_are_only_created_for_params_they_have() {
+    let lister = Lister::new();
+    let overrides = |name: &str| vec![(name.to_string(), "0.5".to_string())];
+    assert!(lister.create_app(&"1.5".parse().unwrap(), &overrides("ratio")).is_ok());
+    // the launcher, run-all and compare all create their apps this way
+    for (id, name) in [("1.5", "raito"), ("1.3", "ratio")] {
+        match lister.create_app(&id.parse().unwrap(), &overrides(name)) {
+            Err(Error::InvalidParam { name: invalid, .. }) => assert_eq!(invalid, name),
+            other => panic!("expected {name} to be rejected for {id}, got {:?}", other.map(|_| ()).map_err(|e| e.to_string())),
+        }
+    }
+}
+
+#[test]
+fn golden_renders_reject_variants_that_dont_exist() {
+    let lister = Lister::new();
+    let runner = lister.find(&"1.3".parse().unwrap()).unwrap();
+    for variant in [0, 4, 99] {
+        assert!(matches!(learn_opengl::golden::render(runner, Some(variant), 1), Err(Error::UnknownId { .. })), "{variant}");
+    }
+}
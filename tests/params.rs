@@ -0,0 +1,51 @@
+use cgmath::vec3;
+use learn_opengl::error::Error;
+use learn_opengl::params::{self, ParamKind, ParamSpec, Params};
+
+const SPECS: &[ParamSpec] = &[
+    ParamSpec { name: "count", kind: ParamKind::Int, default: "3", description: "" },
+    ParamSpec { name: "ratio", kind: ParamKind::Float, default: "0.5", description: "" },
+    ParamSpec { name: "points", kind: ParamKind::Vec3List, default: "0,0,0; 1,2,3", description: "" },
+];
+
+fn overrides(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn defaults_apply_without_overrides() {
+    let params = Params::resolve(SPECS, &[]).unwrap();
+    assert_eq!(params.int("count"), 3);
+    assert_eq!(params.float("ratio"), 0.5);
+    assert_eq!(params.vec3_list("points"), [vec3(0.0, 0.0, 0.0), vec3(1.0, 2.0, 3.0)]);
+}
+
+#[test]
+fn the_last_override_wins() {
+    let params = Params::resolve(SPECS, &overrides(&[("count", "5"), ("count", "7")])).unwrap();
+    assert_eq!(params.int("count"), 7);
+}
+
+#[test]
+fn values_must_parse_as_their_kind() {
+    for (name, value) in [("count", "many"), ("ratio", "1,5"), ("points", "1,2"), ("points", "1,2,x")] {
+        match Params::resolve(SPECS, &overrides(&[(name, value)])) {
+            Err(Error::InvalidParam { name: invalid, .. }) => assert_eq!(invalid, name),
+            other => panic!("expected {name}={value} to be invalid, got {:?}", other.map_err(|e| e.to_string())),
+        }
+    }
+}
+
+#[test]
+fn unknown_names_are_rejected() {
+    assert!(Params::check_names(SPECS, &overrides(&[("ratio", "1")])).is_ok());
+    assert!(matches!(Params::check_names(SPECS, &overrides(&[("colour", "1")])), Err(Error::InvalidParam { .. })));
+    assert!(matches!(Params::check_names(&[], &overrides(&[("ratio", "1")])), Err(Error::InvalidParam { .. })));
+    assert!(matches!(Params::resolve(SPECS, &overrides(&[("colour", "1")])), Err(Error::InvalidParam { .. })));
+}
+
+#[test]
+fn overrides_split_on_the_first_equals_sign() {
+    assert_eq!(params::parse_override("path=a=b.png").unwrap(), ("path".to_string(), "a=b.png".to_string()));
+    assert!(params::parse_override("ratio").is_err());
+}
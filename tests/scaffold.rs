@@ -0,0 +1,65 @@
+use learn_opengl::error::Error;
+use learn_opengl::lister::{Lister, RunID};
+use learn_opengl::scaffold::Scaffold;
+
+fn scaffold(id: &str, name: &str) -> Result<Scaffold, Error> {
+    Scaffold::new(&id.parse::<RunID>().unwrap(), name, &Lister::new())
+}
+
+#[test]
+fn names_follow_the_repository_conventions() {
+    let scaffold = scaffold("2.5", "Light-Casters").unwrap();
+    assert_eq!(scaffold.name(), "light casters");
+    assert_eq!(scaffold.module(), "light_casters");
+    assert_eq!(scaffold.type_name(), "LightCasters");
+    assert_eq!(scaffold.shader_stem(), "2.5.light_casters");
+}
+
+#[test]
+fn existing_exercises_and_bad_names_are_rejected() {
+    assert!(matches!(scaffold("1.8", "fly around"), Err(Error::IdTaken { .. })));
+    assert!(matches!(scaffold("9.1", "camera"), Err(Error::IdTaken { .. })));
+    assert!(matches!(scaffold("9.1.2", "variant"), Err(Error::InvalidId { .. })));
+    assert!(matches!(scaffold("9.1", "3d"), Err(Error::InvalidId { .. })));
+    assert!(matches!(scaffold("9.1", "bad!"), Err(Error::InvalidId { .. })));
+}
+
+#[test]
+fn names_that_would_not_compile_are_rejected() {
+    // keywords can't name the module or struct, the others clash with names the template uses
+    for name in ["type", "loop", "self", "context", "result", "metadata", "runner", "app", "option", "some"] {
+        assert!(matches!(scaffold("9.1", name), Err(Error::InvalidId { .. })), "{name} was accepted");
+    }
+    assert!(scaffold("9.1", "types").is_ok());
+    assert!(scaffold("9.1", "context menu").is_ok());
+}
+
+#[test]
+fn generate_writes_the_runner_shaders_and_module() {
+    let root = std::env::temp_dir().join(format!("learn_opengl_scaffold_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src/exercises")).unwrap();
+    std::fs::create_dir_all(root.join("shaders")).unwrap();
+    std::fs::write(root.join("src/exercises/mod.rs"), "pub mod camera;").unwrap();
+
+    let scaffold = scaffold("9.1", "new exercise").unwrap();
+    scaffold.generate(&root).unwrap();
+
+    let runner = std::fs::read_to_string(root.join("src/exercises/new_exercise.rs")).unwrap();
+    assert!(runner.contains("crate::register_runner!(NewExercise);"));
+    assert!(runner.contains("fn chapter(&self) -> i32 { 9 }"));
+    assert!(runner.contains("\"shaders/9.1.new_exercise.vert\""));
+    assert!(!runner.contains("__"), "placeholders left in:\n{runner}");
+    assert!(root.join("shaders/9.1.new_exercise.vert").is_file());
+    assert!(root.join("shaders/9.1.new_exercise.frag").is_file());
+    assert_eq!(std::fs::read_to_string(root.join("src/exercises/mod.rs")).unwrap(), "pub mod camera;\npub mod new_exercise;\n");
+
+    // a second run must not overwrite the edited files
+    assert!(matches!(scaffold.generate(&root), Err(Error::AssetIo { .. })));
+
+    // rustfmt fails on source that doesn't parse, skipped where it isn't installed
+    match std::process::Command::new("rustfmt").args(["--edition", "2021", "--emit", "stdout"]).arg(root.join("src/exercises/new_exercise.rs")).output() {
+        Ok(output) => assert!(output.status.success(), "the generated runner doesn't parse:\n{}", String::from_utf8_lossy(&output.stderr)),
+        Err(e) => eprintln!("skipping the parse check of the generated runner: rustfmt can't run: {e}"),
+    }
+}
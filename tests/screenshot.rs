@@ -0,0 +1,12 @@
+use learn_opengl::screenshot::timestamped_path;
+
+#[test]
+fn hotkey_screenshots_of_one_run_get_distinct_names() {
+    // both captures land in the same second, and usually the same millisecond
+    let first = timestamped_path("hello triangle", 10);
+    let second = timestamped_path("hello triangle", 11);
+    assert_ne!(first, second);
+
+    let name = first.to_str().unwrap();
+    assert!(name.starts_with("hello_triangle-") && name.ends_with("-frame10.png"), "{name}");
+}
@@ -0,0 +1,233 @@
+use learn_opengl::app::{self, App, Backend, Context, Settings, WindowSettings};
+use learn_opengl::error::Error;
+use learn_opengl::golden;
+use learn_opengl::shader::{memory_barrier, Barrier, ComputeShader, ShaderBuilder, ShaderStage};
+
+const VERTEX_SHADER: &str = r#"
+    #version 330 core
+    void main() {
+        gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+    }
+"#;
+
+/// Turns the single point into a quad over the whole viewport
+const GEOMETRY_SHADER: &str = r#"
+    #version 330 core
+    layout (points) in;
+    layout (triangle_strip, max_vertices = 4) out;
+    void main() {
+        for (int i = 0; i < 4; i++) {
+            gl_Position = vec4(float(i & 1) * 2.0 - 1.0, float(i >> 1) * 2.0 - 1.0, 0.0, 1.0);
+            EmitVertex();
+        }
+        EndPrimitive();
+    }
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    out vec4 FragColor;
+    void main() {
+        FragColor = vec4(1.0, 0.0, 0.0, 1.0);
+    }
+"#;
+
+/// Four vertices of a quad over the whole viewport, made from the vertex ids
+const PATCH_VERTEX_SHADER: &str = r#"
+    #version 400 core
+    void main() {
+        gl_Position = vec4(float(gl_VertexID & 1) * 2.0 - 1.0, float(gl_VertexID >> 1) * 2.0 - 1.0, 0.0, 1.0);
+    }
+"#;
+
+const TESS_CONTROL_SHADER: &str = r#"
+    #version 400 core
+    layout (vertices = 4) out;
+    void main() {
+        gl_out[gl_InvocationID].gl_Position = gl_in[gl_InvocationID].gl_Position;
+        if (gl_InvocationID == 0) {
+            gl_TessLevelOuter[0] = 4.0;
+            gl_TessLevelOuter[1] = 4.0;
+            gl_TessLevelOuter[2] = 4.0;
+            gl_TessLevelOuter[3] = 4.0;
+            gl_TessLevelInner[0] = 4.0;
+            gl_TessLevelInner[1] = 4.0;
+        }
+    }
+"#;
+
+/// Interpolates the quad's corners over the tessellated domain
+const TESS_EVALUATION_SHADER: &str = r#"
+    #version 400 core
+    layout (quads) in;
+    void main() {
+        vec4 bottom = mix(gl_in[0].gl_Position, gl_in[1].gl_Position, gl_TessCoord.x);
+        vec4 top = mix(gl_in[2].gl_Position, gl_in[3].gl_Position, gl_TessCoord.x);
+        gl_Position = mix(bottom, top, gl_TessCoord.y);
+    }
+"#;
+
+/// Writes `factor` times its index into each element of the buffer, the last work group is only partly used
+const COMPUTE_SHADER: &str = r#"
+    #version 430 core
+    layout (local_size_x = 16) in;
+    layout (std430, binding = 0) buffer Values {
+        uint values[];
+    };
+    uniform uint count;
+    uniform uint factor;
+    void main() {
+        uint index = gl_GlobalInvocationID.x;
+        if (index < count) {
+            values[index] = index * factor;
+        }
+    }
+"#;
+
+fn headless_context(gl_version: (u32, u32)) -> Option<Context> {
+    golden::headless_or_skip(&Settings { window: WindowSettings { gl_version, ..WindowSettings::default() }, ..Settings::default() })
+}
+
+/// Draws with an empty vertex array, the shaders make up their vertices, and returns the color at the center
+/// and in a corner
+fn draw(ctx: &Context, mode: gl::types::GLenum, count: i32) -> [[u8; 3]; 2] {
+    let mut vao = 0;
+    unsafe {
+        gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT);
+        gl::GenVertexArrays(1, &mut vao);
+        gl::BindVertexArray(vao);
+        gl::DrawArrays(mode, 0, count);
+        gl::DeleteVertexArrays(1, &vao);
+    }
+    let image = ctx.capture();
+    [image.get_pixel(image.width() / 2, image.height() / 2).0, image.get_pixel(0, 0).0]
+}
+
+#[test]
+fn geometry_stage_emits_primitives() {
+    let Some(ctx) = headless_context((3, 3)) else {
+        return;
+    };
+    let shader = ShaderBuilder::vertex_source(VERTEX_SHADER)
+        .geometry_source(GEOMETRY_SHADER)
+        .fragment_source(FRAGMENT_SHADER)
+        .build()
+        .unwrap();
+
+    shader.use_program();
+    assert_eq!(draw(&ctx, gl::POINTS, 1), [[255, 0, 0]; 2]);
+}
+
+#[test]
+fn geometry_errors_name_the_stage_and_file() {
+    let Some(_ctx) = headless_context((3, 3)) else {
+        return;
+    };
+    let path = std::env::temp_dir().join(format!("learn_opengl_broken_{}.geom", std::process::id()));
+    std::fs::write(&path, GEOMETRY_SHADER.replace("EndPrimitive();", "EndPrimitive()")).unwrap();
+
+    let result = ShaderBuilder::vertex_source(VERTEX_SHADER).geometry(&path).fragment_source(FRAGMENT_SHADER).build();
+    std::fs::remove_file(&path).unwrap();
+
+    let Err(Error::Shader(error)) = result else {
+        panic!("the broken geometry shader compiled");
+    };
+    assert_eq!(error.stage, Some(ShaderStage::Geometry));
+    assert_eq!(error.path, Some(path));
+    assert!(!error.log.is_empty());
+}
+
+#[test]
+fn tessellation_stages_subdivide_patches() {
+    let Some(ctx) = headless_context((4, 0)) else {
+        return;
+    };
+    let shader = ShaderBuilder::vertex_source(PATCH_VERTEX_SHADER)
+        .tess_control_source(TESS_CONTROL_SHADER)
+        .tess_evaluation_source(TESS_EVALUATION_SHADER)
+        .fragment_source(FRAGMENT_SHADER)
+        .build()
+        .unwrap();
+
+    shader.use_program();
+    unsafe { gl::PatchParameteri(gl::PATCH_VERTICES, 4) };
+    assert_eq!(draw(&ctx, gl::PATCHES, 4), [[255, 0, 0]; 2]);
+}
+
+#[test]
+fn compute_shader_dispatches_enough_work_groups() {
+    let Some(_ctx) = headless_context((4, 3)) else {
+        return;
+    };
+    let compute = ComputeShader::from_source(COMPUTE_SHADER).unwrap();
+    assert_eq!(compute.work_group_size(), [16, 1, 1]);
+
+    const COUNT: usize = 100;
+    let mut buffer = 0;
+    let mut values = [0u32; COUNT];
+    unsafe {
+        gl::GenBuffers(1, &mut buffer);
+        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, buffer);
+        gl::BufferData(gl::SHADER_STORAGE_BUFFER, std::mem::size_of_val(&values) as isize, values.as_ptr().cast(), gl::DYNAMIC_READ);
+        gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 0, buffer);
+    }
+
+    compute.use_program();
+    compute.set("count", COUNT as u32);
+    compute.set("factor", 3u32);
+    compute.dispatch_for([COUNT as u32, 1, 1]);
+    memory_barrier(Barrier::BUFFER_UPDATE | Barrier::SHADER_STORAGE);
+
+    unsafe {
+        gl::GetBufferSubData(gl::SHADER_STORAGE_BUFFER, 0, std::mem::size_of_val(&values) as isize, values.as_mut_ptr().cast());
+        gl::DeleteBuffers(1, &buffer);
+    }
+    assert!(values.iter().enumerate().all(|(index, &value)| value == index as u32 * 3), "{values:?}");
+}
+
+#[test]
+fn dropping_a_shader_deletes_its_program() {
+    let Some(_ctx) = headless_context((3, 3)) else {
+        return;
+    };
+    let shader = ShaderBuilder::vertex_source(VERTEX_SHADER).fragment_source(FRAGMENT_SHADER).build().unwrap();
+    let id = shader.get_id();
+    assert_eq!(unsafe { gl::IsProgram(id) }, gl::TRUE);
+
+    drop(shader);
+    assert_eq!(unsafe { gl::IsProgram(id) }, gl::FALSE);
+}
+
+/// Builds a shader, then fails like an exercise whose texture is missing
+#[derive(Default)]
+struct FailingSetup {
+    shader: Option<learn_opengl::shader::Shader>,
+    torn_down_with: Option<gl::types::GLuint>,
+}
+
+impl App for FailingSetup {
+    fn setup(&mut self, _ctx: &mut Context) -> learn_opengl::error::Result<()> {
+        self.shader = Some(ShaderBuilder::vertex_source(VERTEX_SHADER).fragment_source(FRAGMENT_SHADER).build()?);
+        Err(Error::ImageDecode { path: "missing.png".into(), reason: "no such file".to_string() })
+    }
+
+    fn render(&mut self, _ctx: &mut Context) {}
+
+    fn teardown(&mut self, _ctx: &mut Context) {
+        let id = self.shader.take().map(|shader| shader.get_id());
+        // the program is only gone if the context was still current when the shader was dropped
+        self.torn_down_with = id.filter(|&id| unsafe { gl::IsProgram(id) } == gl::FALSE);
+    }
+}
+
+#[test]
+fn failed_setups_are_torn_down_with_the_context_current() {
+    if headless_context((3, 3)).is_none() {
+        return;
+    }
+    let settings = Settings { backend: Backend::Headless, ..Settings::default() };
+    let mut app = FailingSetup::default();
+    assert!(app::render_frames("failing", &mut app, &settings, 1).is_err());
+    assert!(app.torn_down_with.is_some());
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+use learn_opengl::shader::ShaderStage;
+use learn_opengl::shader_error::{Diagnostic, ShaderError};
+
+fn diagnostic(line: u32, message: &str) -> Option<Diagnostic> {
+    Some(Diagnostic { line, message: message.to_string() })
+}
+
+#[test]
+fn driver_log_formats_are_parsed() {
+    assert_eq!(Diagnostic::parse("0:13(17): error: `foo' undeclared"), diagnostic(13, "error: `foo' undeclared"));
+    assert_eq!(Diagnostic::parse("0(13) : error C1008: undefined variable \"foo\""), diagnostic(13, "error C1008: undefined variable \"foo\""));
+    assert_eq!(Diagnostic::parse("ERROR: 0:13: 'foo' : undeclared identifier"), diagnostic(13, "error: 'foo' : undeclared identifier"));
+    assert_eq!(Diagnostic::parse("WARNING: 0:2: extension not supported"), diagnostic(2, "warning: extension not supported"));
+}
+
+#[test]
+fn unlocated_lines_are_skipped() {
+    assert_eq!(Diagnostic::parse("error: linking with uncompiled/unspecialized shader"), None);
+    assert_eq!(Diagnostic::parse("ERROR: 2 compilation errors.  No code generated."), None);
+    assert_eq!(Diagnostic::parse("Fragment info"), None);
+    assert_eq!(Diagnostic::parse(""), None);
+}
+
+#[test]
+fn compile_errors_show_the_lines_they_point_at() {
+    let source = "#version 330 core\nout vec4 FragColor;\nvoid main() {\n    FragColor = foo;\n}\n";
+    let log = "0:4(17): error: `foo' undeclared\n0:4(5): error: value of type error cannot be assigned".to_string();
+    let error = ShaderError::compile(ShaderStage::Fragment, Some(Path::new("shaders/test.frag")), source, log);
+
+    assert_eq!(error.diagnostics.len(), 2);
+    assert_eq!(error.to_string(), "\
+failed to compile fragment shader shaders/test.frag:
+error: `foo' undeclared
+ --> shaders/test.frag:4
+  |
+3 | void main() {
+4 |     FragColor = foo;
+
+error: value of type error cannot be assigned
+ --> shaders/test.frag:4
+  |
+3 | void main() {
+4 |     FragColor = foo;");
+}
+
+#[test]
+fn unrecognized_logs_are_shown_in_full() {
+    let error = ShaderError::link("error: fragment shader output FragColor not written\nlink failed".to_string());
+    assert!(error.diagnostics.is_empty());
+    assert_eq!(error.to_string(), "failed to link shader program:\nerror: fragment shader output FragColor not written\nlink failed");
+}
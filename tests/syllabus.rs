@@ -0,0 +1,19 @@
+use std::path::Path;
+use learn_opengl::lister::Lister;
+use learn_opengl::syllabus;
+
+#[test]
+fn readme_checklist_is_up_to_date() {
+    let readme = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("readme.md")).unwrap();
+    let updated = syllabus::update_readme(&readme, &Lister::new()).expect("readme.md has the checklist markers");
+    assert!(readme == updated, "readme.md is out of date, run `cargo run -- status --update-readme`");
+}
+
+#[test]
+fn sections_are_numbered_from_one() {
+    assert_eq!(syllabus::section_title(1, 1), Some("Creating a window"));
+    assert_eq!(syllabus::section_title(1, 8), Some("Camera"));
+    assert_eq!(syllabus::section_title(8, 10), Some("Area Lights"));
+    assert_eq!(syllabus::section_title(1, 0), None);
+    assert_eq!(syllabus::section_title(9, 1), None);
+}
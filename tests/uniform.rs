@@ -0,0 +1,114 @@
+use cgmath::{Matrix3, Point3, Vector2, Vector3};
+use learn_opengl::app::Settings;
+use learn_opengl::golden;
+use learn_opengl::shader::Shader;
+use learn_opengl::uniform::Sampler;
+
+const VERTEX_SHADER: &str = r#"
+    #version 330 core
+    void main() {
+        gl_Position = vec4(0.0);
+    }
+"#;
+
+// every uniform feeds the output so none of them are optimized out
+const FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    out vec4 FragColor;
+    uniform float scale;
+    uniform uint count;
+    uniform bool enabled;
+    uniform vec3 color;
+    uniform vec3 position;
+    uniform mat3 normal_matrix;
+    uniform vec2 offsets[2];
+    uniform sampler2D image;
+    void main() {
+        vec3 value = normal_matrix * color * scale * float(count) + position;
+        value.xy += offsets[0] + offsets[1];
+        FragColor = enabled ? texture(image, value.xy) : vec4(value, 1.0);
+    }
+"#;
+
+/// Reads `N` floats of a uniform back from the program
+fn read<const N: usize>(shader: &Shader, name: &str) -> [f32; N] {
+    let mut values = [0.0; N];
+    unsafe { gl::GetUniformfv(shader.get_id(), shader.uniform_location(name), values.as_mut_ptr()) };
+    values
+}
+
+fn read_int(shader: &Shader, name: &str) -> i32 {
+    let mut value = 0;
+    unsafe { gl::GetUniformiv(shader.get_id(), shader.uniform_location(name), &mut value) };
+    value
+}
+
+#[test]
+fn values_reach_the_uniforms_of_their_type() {
+    let Some(_ctx) = golden::headless_or_skip(&Settings::default()) else {
+        return;
+    };
+    let shader = Shader::from_source(VERTEX_SHADER, FRAGMENT_SHADER).unwrap();
+    shader.use_program();
+
+    let offsets = vec![Vector2::new(1.0, 2.0), Vector2::new(3.0, 4.0)];
+    let normal_matrix = Matrix3::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0);
+    shader.set("scale", 0.5f32);
+    shader.set("count", 3u32);
+    shader.set("enabled", true);
+    shader.set("color", Vector3::new(0.1, 0.2, 0.3));
+    shader.set("position", Point3::new(1.0, 2.0, 3.0));
+    shader.set("normal_matrix", normal_matrix);
+    shader.set("offsets", offsets.as_slice());
+    shader.set("image", Sampler(2));
+
+    assert_eq!(read::<1>(&shader, "scale"), [0.5]);
+    assert_eq!(read_int(&shader, "count"), 3);
+    assert_eq!(read_int(&shader, "enabled"), 1);
+    assert_eq!(read::<3>(&shader, "color"), [0.1, 0.2, 0.3]);
+    assert_eq!(read::<3>(&shader, "position"), [1.0, 2.0, 3.0]);
+    // both cgmath and OpenGL store matrices by column
+    assert_eq!(read::<9>(&shader, "normal_matrix"), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+    assert_eq!(read::<2>(&shader, "offsets[1]"), [3.0, 4.0]);
+    assert_eq!(read_int(&shader, "image"), 2);
+}
+
+/// Set when the test binary runs itself to collect the warnings `missing_uniform_lookup` prints
+const CHILD_ENV: &str = "LEARN_OPENGL_UNIFORM_CHILD";
+
+/// Looks up a misspelled uniform twice, only does something when started by `missing_uniforms_warn_once`
+#[test]
+fn missing_uniform_lookup() {
+    if std::env::var_os(CHILD_ENV).is_none() {
+        return;
+    }
+    let Some(_ctx) = golden::headless_or_skip(&Settings::default()) else {
+        return;
+    };
+    let shader = Shader::from_source(VERTEX_SHADER, FRAGMENT_SHADER).unwrap();
+    shader.use_program();
+
+    assert_eq!(shader.uniform_location("colour"), -1);
+    shader.set("colour", Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(shader.missing_uniforms(), ["colour"]);
+    // found uniforms are cached as well but aren't missing
+    assert_ne!(shader.uniform_location("color"), -1);
+    assert_eq!(shader.missing_uniforms(), ["colour"]);
+}
+
+#[test]
+fn missing_uniforms_warn_once() {
+    if golden::headless_or_skip(&Settings::default()).is_none() {
+        return;
+    }
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "missing_uniform_lookup", "--nocapture", "--test-threads", "1"])
+        .env(CHILD_ENV, "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert_eq!(stderr.matches("warning:").count(), 1, "{stderr}");
+    assert!(stderr.contains("\"colour\""), "{stderr}");
+}